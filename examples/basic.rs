@@ -15,12 +15,10 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use mycobot_rs::MyCobot;
-use serial2::SerialPort as SysSerial;
+use mycobot_rs::MyCobotBuilder;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
-    let port = SysSerial::open("/dev/ttyAMA0", 1_000_000)?;
-    let mut robot = MyCobot::new(port);
+    let mut robot = MyCobotBuilder::new("/dev/ttyAMA0", 1_000_000).build()?;
 
     robot.power_on()?;
 
@@ -0,0 +1,220 @@
+/*
+ * Copyright (C) 2026 Progress Robotics UG
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Async variant of [`MyCobot`](crate::MyCobot), enabled with the `tokio` feature.
+
+use crate::commands::{Command, MoveMode};
+use crate::model::RobotModel;
+use crate::protocol::{Packet, HEADER};
+use crate::types::{ClampMode, Coords, JointAngles};
+use crate::robot::{angles_payload, coords_payload, decode_angles, decode_coords, parse_response, Error, Result, DEFAULT_TIMEOUT, READ_CHUNK_LEN};
+use std::io;
+use std::time::Duration;
+use log::{debug, warn};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+pub struct AsyncMyCobot<P: AsyncRead + AsyncWrite + Unpin> {
+    pub port: P,
+    debug_mode: bool,
+    model: RobotModel,
+    /// Timeout used by all requests.
+    timeout: Duration,
+    /// What happens to targets outside `angle_limits` and `coord_limits`.
+    clamp_mode: ClampMode,
+    /// (min, max) per joint in degrees, checked before any angle is sent.
+    angle_limits: [(f32, f32); 6],
+    /// (min, max) per axis, checked before any coordinate is sent.
+    coord_limits: [(f32, f32); 6],
+    /// Received bytes not yet parsed into a packet.
+    rx_buffer: Vec<u8>,
+}
+
+impl<P: AsyncRead + AsyncWrite + Unpin> AsyncMyCobot<P> {
+    /// Wraps `port` talking to a MyCobot 280.
+    pub fn new(port: P) -> Self {
+        Self::with_model(port, RobotModel::MyCobot280)
+    }
+
+    pub fn with_model(port: P, model: RobotModel) -> Self {
+        Self {
+            port,
+            debug_mode: false,
+            model,
+            timeout: DEFAULT_TIMEOUT,
+            clamp_mode: ClampMode::default(),
+            angle_limits: model.angle_limits(),
+            coord_limits: model.coord_limits(),
+            rx_buffer: Vec::new(),
+        }
+    }
+
+    /// Consumes the robot and returns the wrapped port.
+    pub fn into_inner(self) -> P {
+        self.port
+    }
+
+    pub fn set_debug_mode(&mut self, debug: bool) {
+        self.debug_mode = debug;
+    }
+
+    /// Sets the response timeout used by all requests.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Makes motion commands clamp targets to the limits instead of
+    /// rejecting them, see `MyCobot::set_clamp_mode`.
+    pub fn set_clamp_mode(&mut self, mode: ClampMode) {
+        self.clamp_mode = mode;
+    }
+
+    /// Overrides the (min, max) angle of each joint in degrees.
+    /// Defaults to the limits of the model.
+    pub fn set_angle_limits(&mut self, limits: [(f32, f32); 6]) {
+        self.angle_limits = limits;
+    }
+
+    pub fn angle_limits(&self) -> [(f32, f32); 6] {
+        self.angle_limits
+    }
+
+    /// Overrides the (min, max) of each Cartesian axis, millimeters and degrees.
+    /// Defaults to the limits of the model.
+    pub fn set_coord_limits(&mut self, limits: [(f32, f32); 6]) {
+        self.coord_limits = limits;
+    }
+
+    pub fn coord_limits(&self) -> [(f32, f32); 6] {
+        self.coord_limits
+    }
+
+    async fn write_command(&mut self, command: Command, payload: Vec<u8>) -> Result<()> {
+        let bytes = Packet::new(command, payload)
+            .try_to_bytes()
+            .map_err(|e| Error::InvalidParameter(format!("{:?}: {}", command, e)))?;
+        if self.debug_mode {
+            debug!("Writing: {:02X?}", bytes);
+        }
+        self.port.write_all(&bytes).await?;
+        self.port.flush().await?;
+        Ok(())
+    }
+
+    async fn request(&mut self, command: Command, payload: Vec<u8>) -> Result<Vec<u8>> {
+        self.write_command(command, payload).await?;
+        match tokio::time::timeout(self.timeout, self.read_response(command)).await {
+            Ok(result) => result,
+            Err(_) => Err(Error::Timeout),
+        }
+    }
+
+    /// Reads until a packet answering `command` is parsed. Bytes after it stay
+    /// in the receive buffer for the next request.
+    async fn read_response(&mut self, command: Command) -> Result<Vec<u8>> {
+        let mut temp_buf = [0u8; READ_CHUNK_LEN];
+
+        loop {
+            while let Some(packet) = self.next_packet() {
+                if self.debug_mode {
+                    debug!("Received: {}", packet.describe());
+                }
+                match parse_response(self.model, command, &packet) {
+                    Ok(()) => return Ok(packet.payload),
+                    Err(e @ Error::UnexpectedCommand { .. }) => warn!("{}", e),
+                    Err(e) => return Err(e),
+                }
+            }
+
+            // The port waits for data, a read of zero bytes means it was closed
+            let n = self.port.read(&mut temp_buf).await?;
+            if n == 0 {
+                return Err(Error::Io(io::Error::new(io::ErrorKind::UnexpectedEof, "Connection closed")));
+            }
+            self.rx_buffer.extend_from_slice(&temp_buf[..n]);
+        }
+    }
+
+    /// Parses the next complete packet from the receive buffer, skipping invalid data.
+    /// Resyncs on the next header the same way as `MyCobot`.
+    fn next_packet(&mut self) -> Option<Packet> {
+        loop {
+            // Drop anything before the next header in one go
+            match Packet::find_header(&self.rx_buffer) {
+                Some(start) => {
+                    self.rx_buffer.drain(..start);
+                }
+                None => {
+                    // Keep a trailing sync byte, it may be the start of a header
+                    let keep = usize::from(self.rx_buffer.last() == Some(&HEADER[0]));
+                    let len = self.rx_buffer.len();
+                    self.rx_buffer.drain(..len - keep);
+                    return None;
+                }
+            }
+
+            match Packet::parse(&self.rx_buffer) {
+                Ok(Some((packet, consumed))) => {
+                    self.rx_buffer.drain(..consumed);
+                    return Some(packet);
+                }
+                Ok(None) => return None,
+                Err(_) => {
+                    // Header without a valid frame, resync after it
+                    self.rx_buffer.drain(..1);
+                }
+            }
+        }
+    }
+
+    pub async fn power_on(&mut self) -> Result<()> {
+        self.write_command(Command::PowerOn, vec![]).await
+    }
+
+    pub async fn power_off(&mut self) -> Result<()> {
+        self.write_command(Command::PowerOff, vec![]).await
+    }
+
+    pub async fn is_powered_on(&mut self) -> Result<bool> {
+        let response = self.request(Command::IsPoweredOn, vec![]).await?;
+        if response.len() == 1 {
+            Ok(response[0] == 1)
+        } else {
+            Err(Error::LengthMismatch { expected: 1, got: response.len() })
+        }
+    }
+
+    pub async fn get_angles(&mut self) -> Result<JointAngles> {
+        let response = self.request(Command::GetAngles, vec![]).await?;
+        Ok(decode_angles(self.model, &response)?.into())
+    }
+
+    pub async fn write_angles(&mut self, angles: impl Into<[f32; 6]>, speed: u8) -> Result<()> {
+        let payload = angles_payload(self.model, self.clamp_mode, &self.angle_limits, angles.into(), speed, 100)?;
+        self.write_command(Command::WriteAngles, payload).await
+    }
+
+    pub async fn get_coords(&mut self) -> Result<Coords> {
+        let response = self.request(Command::GetCoords, vec![]).await?;
+        Ok(decode_coords(self.model, &response)?.into())
+    }
+
+    pub async fn write_coords(&mut self, coords: impl Into<[f32; 6]>, speed: u8, mode: MoveMode) -> Result<()> {
+        let payload =
+            coords_payload(self.model, self.clamp_mode, &self.coord_limits, coords.into(), speed, 100, mode)?;
+        self.write_command(Command::WriteCoords, payload).await
+    }
+}
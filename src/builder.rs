@@ -0,0 +1,121 @@
+/*
+ * Copyright (C) 2026 Progress Robotics UG
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+use crate::io::SerialPort;
+use crate::model::RobotModel;
+use crate::robot::{MyCobot, Result, DEFAULT_TIMEOUT};
+use std::path::PathBuf;
+use std::time::Duration;
+use log::warn;
+
+/// Collects connection and robot settings and opens the serial port.
+///
+/// ```no_run
+/// use mycobot_rs::MyCobotBuilder;
+/// use std::time::Duration;
+///
+/// let robot = MyCobotBuilder::new("/dev/ttyAMA0", 1_000_000)
+///     .timeout(Duration::from_secs(1))
+///     .debug(true)
+///     .build()?;
+/// # Ok::<(), mycobot_rs::Error>(())
+/// ```
+#[derive(Debug, Clone)]
+pub struct MyCobotBuilder {
+    path: PathBuf,
+    baud_rate: u32,
+    timeout: Duration,
+    debug: bool,
+    retries: u32,
+    read_retries: u32,
+    model: RobotModel,
+}
+
+impl MyCobotBuilder {
+    pub fn new(path: impl Into<PathBuf>, baud_rate: u32) -> Self {
+        Self {
+            path: path.into(),
+            baud_rate,
+            timeout: DEFAULT_TIMEOUT,
+            debug: false,
+            retries: 0,
+            read_retries: 0,
+            model: RobotModel::default(),
+        }
+    }
+
+    /// Default response timeout for all requests.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn debug(mut self, debug: bool) -> Self {
+        self.debug = debug;
+        self
+    }
+
+    /// Number of additional attempts to open the port if the first one fails.
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Number of times a request is re-sent after a response of the wrong length.
+    pub fn read_retries(mut self, retries: u32) -> Self {
+        self.read_retries = retries;
+        self
+    }
+
+    /// The arm connected to the port, defaults to the MyCobot 280.
+    pub fn model(mut self, model: RobotModel) -> Self {
+        self.model = model;
+        self
+    }
+
+    /// Opens the serial port and returns the configured robot.
+    pub fn build(self) -> Result<MyCobot<serial2::SerialPort>> {
+        let mut attempt = 0;
+        let mut robot = loop {
+            match MyCobot::open(&self.path, self.baud_rate) {
+                Ok(robot) => break robot,
+                Err(e) if attempt < self.retries => {
+                    attempt += 1;
+                    warn!("Failed to open {}: {}, retrying ({}/{})", self.path.display(), e, attempt, self.retries);
+                }
+                Err(e) => return Err(e),
+            }
+        };
+        self.configure(&mut robot);
+        Ok(robot)
+    }
+
+    /// Applies the settings to an already opened port, e.g. a `MockSerial`.
+    /// The path and baud rate are ignored.
+    pub fn build_with_port<P: SerialPort>(self, port: P) -> MyCobot<P> {
+        let mut robot = MyCobot::new(port);
+        self.configure(&mut robot);
+        robot
+    }
+
+    fn configure<P: SerialPort>(&self, robot: &mut MyCobot<P>) {
+        robot.set_model(self.model);
+        robot.set_timeout(self.timeout);
+        robot.set_debug_mode(self.debug);
+        robot.set_read_retries(self.read_retries);
+    }
+}
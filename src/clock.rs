@@ -0,0 +1,100 @@
+/*
+ * Copyright (C) 2026 Progress Robotics UG
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Time source used for timeouts, polling and pacing.
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+    fn sleep(&self, duration: Duration);
+}
+
+/// The system clock, used by default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        std::thread::sleep(duration);
+    }
+}
+
+/// Clock for tests that only moves when slept on or advanced, so timeouts
+/// expire instantly. Clones share the same time.
+#[derive(Debug, Clone)]
+pub struct FakeClock {
+    start: Instant,
+    elapsed: Arc<Mutex<Duration>>,
+}
+
+impl FakeClock {
+    pub fn new() -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed: Arc::new(Mutex::new(Duration::ZERO)),
+        }
+    }
+
+    /// Moves the clock forward by `duration`.
+    pub fn advance(&self, duration: Duration) {
+        *self.elapsed.lock().unwrap() += duration;
+    }
+
+    /// Total time the clock was advanced or slept.
+    pub fn elapsed(&self) -> Duration {
+        *self.elapsed.lock().unwrap()
+    }
+}
+
+impl Default for FakeClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for FakeClock {
+    fn now(&self) -> Instant {
+        self.start + self.elapsed()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        self.advance(duration);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_fake_clock_advances_on_sleep() {
+        let clock = FakeClock::new();
+        let start = clock.now();
+
+        clock.sleep(Duration::from_secs(10));
+        clock.clone().advance(Duration::from_millis(5));
+
+        assert_eq!(clock.now() - start, Duration::from_millis(10_005));
+        assert_eq!(clock.elapsed(), Duration::from_millis(10_005));
+    }
+}
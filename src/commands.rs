@@ -29,6 +29,8 @@ pub enum Command {
     ReleaseAllServos = 0x13,
     IsControllerConnected = 0x14,
     ReadNextError = 0x15,
+    SetFreshMode = 0x16,
+    GetFreshMode = 0x17,
     SetFreeMoveMode = 0x1A,
     IsFreeMoveMode = 0x1B,
 
@@ -45,6 +47,7 @@ pub enum Command {
     TaskStop = 0x29,
     IsInPosition = 0x2A,
     CheckRunning = 0x2B,
+    GetCoord = 0x2D,
 
     // Jogging
     JogAngle = 0x30,
@@ -63,18 +66,38 @@ pub enum Command {
     GetSpeed = 0x40,
     SetSpeed = 0x41,
     
+    // Servo control
+    SetServoCalibration = 0x54,
+    ReleaseServo = 0x56,
+    FocusServo = 0x57,
+
     // IO
     SetPinMode = 0x60,
     SetDigitalOut = 0x61,
     GetDigitalIn = 0x62,
     
     // Gripper / LED
+    GetGripperValue = 0x65,
     GripperMode = 0x66,
+    SetGripperValue = 0x67,
+    IsGripperMoving = 0x69,
     SetLedRgb = 0x6A,
+    GetGripperType = 0x6C,
+
+    // Coordinate frames
+    SetToolReference = 0x81,
+    SetWorldReference = 0x82,
+    GetToolReference = 0x83,
+    GetWorldReference = 0x84,
     
     // Basic
     SetBasicOut = 0xA0,
     GetBasicIn = 0xA1,
+
+    // Servo diagnostics
+    GetServoCurrents = 0xE2,
+    GetServoStatus = 0xE4,
+    GetServoTemps = 0xE5,
     
     // Fallback
     Unknown(u8),
@@ -83,25 +106,63 @@ pub enum Command {
 impl From<u8> for Command {
     fn from(byte: u8) -> Self {
         match byte {
+            0x00 => Command::Undefined,
+            0x01 => Command::Version,
             0x10 => Command::PowerOn,
             0x11 => Command::PowerOff,
             0x12 => Command::IsPoweredOn,
             0x13 => Command::ReleaseAllServos,
             0x14 => Command::IsControllerConnected,
+            0x15 => Command::ReadNextError,
+            0x16 => Command::SetFreshMode,
+            0x17 => Command::GetFreshMode,
+            0x1A => Command::SetFreeMoveMode,
+            0x1B => Command::IsFreeMoveMode,
             0x20 => Command::GetAngles,
             0x21 => Command::WriteAngle,
             0x22 => Command::WriteAngles,
             0x23 => Command::GetCoords,
             0x24 => Command::WriteCoord,
             0x25 => Command::WriteCoords,
+            0x26 => Command::ProgramPause,
+            0x27 => Command::IsProgramPaused,
+            0x28 => Command::ProgramResume,
+            0x29 => Command::TaskStop,
             0x2A => Command::IsInPosition,
             0x2B => Command::CheckRunning,
+            0x2D => Command::GetCoord,
+            0x30 => Command::JogAngle,
+            0x31 => Command::JogAbsolute,
+            0x32 => Command::JogCoord,
+            0x33 => Command::SendJogIncrement,
+            0x34 => Command::JogStop,
+            0x3A => Command::SetEncoder,
+            0x3B => Command::GetEncoder,
+            0x3C => Command::SetEncoders,
+            0x3D => Command::GetEncoders,
             0x40 => Command::GetSpeed,
             0x41 => Command::SetSpeed,
+            0x54 => Command::SetServoCalibration,
+            0x56 => Command::ReleaseServo,
+            0x57 => Command::FocusServo,
+            0x60 => Command::SetPinMode,
+            0x61 => Command::SetDigitalOut,
+            0x62 => Command::GetDigitalIn,
+            0x65 => Command::GetGripperValue,
+            0x66 => Command::GripperMode,
+            0x67 => Command::SetGripperValue,
+            0x69 => Command::IsGripperMoving,
             0x6A => Command::SetLedRgb,
+            0x6C => Command::GetGripperType,
+            0x81 => Command::SetToolReference,
+            0x82 => Command::SetWorldReference,
+            0x83 => Command::GetToolReference,
+            0x84 => Command::GetWorldReference,
             0xA0 => Command::SetBasicOut,
             0xA1 => Command::GetBasicIn,
-            // ... add others as needed
+            0xE2 => Command::GetServoCurrents,
+            0xE4 => Command::GetServoStatus,
+            0xE5 => Command::GetServoTemps,
             b => Command::Unknown(b),
         }
     }
@@ -110,27 +171,331 @@ impl From<u8> for Command {
 impl From<Command> for u8 {
     fn from(cmd: Command) -> Self {
         match cmd {
+            Command::Undefined => 0x00,
+            Command::Version => 0x01,
             Command::PowerOn => 0x10,
             Command::PowerOff => 0x11,
             Command::IsPoweredOn => 0x12,
             Command::ReleaseAllServos => 0x13,
             Command::IsControllerConnected => 0x14,
+            Command::ReadNextError => 0x15,
+            Command::SetFreshMode => 0x16,
+            Command::GetFreshMode => 0x17,
+            Command::SetFreeMoveMode => 0x1A,
+            Command::IsFreeMoveMode => 0x1B,
             Command::GetAngles => 0x20,
             Command::WriteAngle => 0x21,
             Command::WriteAngles => 0x22,
             Command::GetCoords => 0x23,
             Command::WriteCoord => 0x24,
             Command::WriteCoords => 0x25,
+            Command::ProgramPause => 0x26,
+            Command::IsProgramPaused => 0x27,
+            Command::ProgramResume => 0x28,
+            Command::TaskStop => 0x29,
             Command::IsInPosition => 0x2A,
             Command::CheckRunning => 0x2B,
+            Command::GetCoord => 0x2D,
+            Command::JogAngle => 0x30,
+            Command::JogAbsolute => 0x31,
+            Command::JogCoord => 0x32,
+            Command::SendJogIncrement => 0x33,
+            Command::JogStop => 0x34,
+            Command::SetEncoder => 0x3A,
+            Command::GetEncoder => 0x3B,
+            Command::SetEncoders => 0x3C,
+            Command::GetEncoders => 0x3D,
             Command::GetSpeed => 0x40,
             Command::SetSpeed => 0x41,
+            Command::SetServoCalibration => 0x54,
+            Command::ReleaseServo => 0x56,
+            Command::FocusServo => 0x57,
+            Command::SetPinMode => 0x60,
+            Command::SetDigitalOut => 0x61,
+            Command::GetDigitalIn => 0x62,
+            Command::GetGripperValue => 0x65,
+            Command::GripperMode => 0x66,
+            Command::SetGripperValue => 0x67,
+            Command::IsGripperMoving => 0x69,
             Command::SetLedRgb => 0x6A,
+            Command::GetGripperType => 0x6C,
+            Command::SetToolReference => 0x81,
+            Command::SetWorldReference => 0x82,
+            Command::GetToolReference => 0x83,
+            Command::GetWorldReference => 0x84,
             Command::SetBasicOut => 0xA0,
             Command::GetBasicIn => 0xA1,
-            // ...
+            Command::GetServoCurrents => 0xE2,
+            Command::GetServoStatus => 0xE4,
+            Command::GetServoTemps => 0xE5,
             Command::Unknown(b) => b,
-            _ => 0x00, // TODO: map all
         }
     }
 }
+
+impl Command {
+    /// Payload length of the controller's response, or `None` for commands
+    /// that are not answered.
+    pub fn expected_response_len(&self) -> Option<usize> {
+        match self {
+            Command::GetAngles
+            | Command::GetCoords
+            | Command::GetEncoders
+            | Command::GetToolReference
+            | Command::GetWorldReference
+            | Command::GetServoCurrents => Some(12),
+            Command::GetEncoder | Command::GetCoord => Some(2),
+            Command::GetServoStatus | Command::GetServoTemps => Some(6),
+            Command::Version
+            | Command::IsPoweredOn
+            | Command::IsControllerConnected
+            | Command::ReadNextError
+            | Command::GetFreshMode
+            | Command::IsFreeMoveMode
+            | Command::IsProgramPaused
+            | Command::IsInPosition
+            | Command::CheckRunning
+            | Command::GetSpeed
+            | Command::GetDigitalIn
+            | Command::GetGripperValue
+            | Command::IsGripperMoving
+            | Command::GetGripperType
+            | Command::GetBasicIn => Some(1),
+            _ => None,
+        }
+    }
+
+    /// Whether the controller answers this command, i.e. whether it has to be
+    /// sent as a request rather than fire-and-forget. `Unknown` commands are
+    /// assumed not to be answered.
+    pub fn expects_response(&self) -> bool {
+        self.expected_response_len().is_some()
+    }
+}
+
+/// Direction of an Atom GPIO pin.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PinMode {
+    Input,
+    Output,
+}
+
+impl From<PinMode> for u8 {
+    fn from(mode: PinMode) -> Self {
+        match mode {
+            PinMode::Input => 0,
+            PinMode::Output => 1,
+        }
+    }
+}
+
+/// Interpolation used by `write_coords`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MoveMode {
+    /// Joint-space (MoveJ) motion, the path is not a straight line.
+    #[default]
+    Angular,
+    /// Straight-line (MoveL) motion of the end effector.
+    Linear,
+}
+
+impl From<MoveMode> for u8 {
+    fn from(mode: MoveMode) -> Self {
+        match mode {
+            MoveMode::Angular => 0,
+            MoveMode::Linear => 1,
+        }
+    }
+}
+
+/// Gripper attached to the end effector, see `MyCobot::set_gripper_type`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GripperType {
+    Adaptive,
+    Parallel,
+    Flexible,
+}
+
+impl From<GripperType> for u8 {
+    fn from(gripper: GripperType) -> Self {
+        match gripper {
+            GripperType::Adaptive => 1,
+            GripperType::Parallel => 3,
+            GripperType::Flexible => 4,
+        }
+    }
+}
+
+impl GripperType {
+    /// Decodes the type byte reported by `GetGripperType`. Returns `None` for
+    /// codes of unknown grippers or when none is attached.
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            1 => Some(GripperType::Adaptive),
+            3 => Some(GripperType::Parallel),
+            4 => Some(GripperType::Flexible),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALL: &[Command] = &[
+        Command::Undefined,
+        Command::Version,
+        Command::PowerOn,
+        Command::PowerOff,
+        Command::IsPoweredOn,
+        Command::ReleaseAllServos,
+        Command::IsControllerConnected,
+        Command::ReadNextError,
+        Command::SetFreshMode,
+        Command::GetFreshMode,
+        Command::SetFreeMoveMode,
+        Command::IsFreeMoveMode,
+        Command::GetAngles,
+        Command::WriteAngle,
+        Command::WriteAngles,
+        Command::GetCoords,
+        Command::WriteCoord,
+        Command::WriteCoords,
+        Command::ProgramPause,
+        Command::IsProgramPaused,
+        Command::ProgramResume,
+        Command::TaskStop,
+        Command::IsInPosition,
+        Command::CheckRunning,
+        Command::GetCoord,
+        Command::JogAngle,
+        Command::JogAbsolute,
+        Command::JogCoord,
+        Command::SendJogIncrement,
+        Command::JogStop,
+        Command::SetEncoder,
+        Command::GetEncoder,
+        Command::SetEncoders,
+        Command::GetEncoders,
+        Command::GetSpeed,
+        Command::SetSpeed,
+        Command::SetServoCalibration,
+        Command::ReleaseServo,
+        Command::FocusServo,
+        Command::SetPinMode,
+        Command::SetDigitalOut,
+        Command::GetDigitalIn,
+        Command::GetGripperValue,
+        Command::GripperMode,
+        Command::SetGripperValue,
+        Command::IsGripperMoving,
+        Command::SetLedRgb,
+        Command::GetGripperType,
+        Command::SetToolReference,
+        Command::SetWorldReference,
+        Command::GetToolReference,
+        Command::GetWorldReference,
+        Command::SetBasicOut,
+        Command::GetBasicIn,
+        Command::GetServoCurrents,
+        Command::GetServoStatus,
+        Command::GetServoTemps,
+    ];
+
+    #[test]
+    fn test_round_trip_known_commands() {
+        for &cmd in ALL {
+            assert_eq!(Command::from(u8::from(cmd)), cmd, "{:?} did not round-trip", cmd);
+        }
+    }
+
+    #[test]
+    fn test_round_trip_all_bytes() {
+        for byte in 0..=u8::MAX {
+            assert_eq!(u8::from(Command::from(byte)), byte);
+        }
+    }
+
+    #[test]
+    fn test_reference_opcodes() {
+        // As numbered by the firmware, sets before gets
+        assert_eq!(u8::from(Command::SetToolReference), 0x81);
+        assert_eq!(u8::from(Command::SetWorldReference), 0x82);
+        assert_eq!(u8::from(Command::GetToolReference), 0x83);
+        assert_eq!(u8::from(Command::GetWorldReference), 0x84);
+    }
+
+    #[test]
+    fn test_expected_response_len() {
+        // Joint angles, coordinates and encoders are six big-endian 16-bit values
+        assert_eq!(Command::GetAngles.expected_response_len(), Some(12));
+        assert_eq!(Command::GetCoords.expected_response_len(), Some(12));
+        assert_eq!(Command::GetEncoders.expected_response_len(), Some(12));
+        assert_eq!(Command::GetToolReference.expected_response_len(), Some(12));
+        assert_eq!(Command::GetWorldReference.expected_response_len(), Some(12));
+        assert_eq!(Command::GetEncoder.expected_response_len(), Some(2));
+        assert_eq!(Command::GetCoord.expected_response_len(), Some(2));
+
+        // Single byte values and booleans
+        for cmd in [
+            Command::Version,
+            Command::IsPoweredOn,
+            Command::IsControllerConnected,
+            Command::ReadNextError,
+            Command::GetFreshMode,
+            Command::IsFreeMoveMode,
+            Command::IsProgramPaused,
+            Command::IsInPosition,
+            Command::CheckRunning,
+            Command::GetSpeed,
+            Command::GetDigitalIn,
+            Command::GetGripperValue,
+            Command::IsGripperMoving,
+            Command::GetGripperType,
+            Command::GetBasicIn,
+        ] {
+            assert_eq!(cmd.expected_response_len(), Some(1), "{:?}", cmd);
+        }
+
+        // Fire-and-forget commands
+        assert_eq!(Command::PowerOn.expected_response_len(), None);
+        assert_eq!(Command::WriteAngles.expected_response_len(), None);
+        assert_eq!(Command::SetLedRgb.expected_response_len(), None);
+    }
+
+    #[test]
+    fn test_expects_response() {
+        let getters = [
+            Command::Version,
+            Command::IsPoweredOn,
+            Command::IsControllerConnected,
+            Command::ReadNextError,
+            Command::GetFreshMode,
+            Command::IsFreeMoveMode,
+            Command::GetAngles,
+            Command::GetCoords,
+            Command::IsProgramPaused,
+            Command::IsInPosition,
+            Command::CheckRunning,
+            Command::GetCoord,
+            Command::GetEncoder,
+            Command::GetEncoders,
+            Command::GetSpeed,
+            Command::GetDigitalIn,
+            Command::GetGripperValue,
+            Command::IsGripperMoving,
+            Command::GetGripperType,
+            Command::GetToolReference,
+            Command::GetWorldReference,
+            Command::GetBasicIn,
+            Command::GetServoCurrents,
+            Command::GetServoStatus,
+            Command::GetServoTemps,
+        ];
+        for &cmd in ALL {
+            assert_eq!(cmd.expects_response(), getters.contains(&cmd), "{:?}", cmd);
+        }
+        assert!(!Command::Unknown(0xFF).expects_response());
+    }
+}
@@ -15,11 +15,62 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use std::io::{self, Read, Write};
+use crate::protocol::Direction;
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{self, BufRead, Read, Write};
+use std::net::{SocketAddr, TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::{Duration, Instant};
 
 /// Trait for serial port communication to allow mocking.
 pub trait SerialPort: io::Read + io::Write + Send {
     fn flush(&mut self) -> io::Result<()>;
+
+    /// Opens the device at `path`. Used by `MyCobot::open` and for reconnecting.
+    /// Ports that don't correspond to a device keep the default, which fails.
+    fn open_device(_path: &Path, _baud_rate: u32) -> io::Result<Self>
+    where
+        Self: Sized,
+    {
+        Err(io::Error::new(io::ErrorKind::Unsupported, "Port cannot be opened from a path"))
+    }
+
+    /// Reads into `buf`, waiting at most `timeout` for data to arrive.
+    /// Returns `ErrorKind::TimedOut` if nothing was received in time.
+    ///
+    /// The default implementation polls `read` with 1ms sleeps for ports that
+    /// have no native timeout support.
+    fn read_with_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        let start = Instant::now();
+        loop {
+            match io::Read::read(self, buf) {
+                Ok(0) => {}
+                Ok(n) => return Ok(n),
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => {}
+                Err(e) => return Err(e),
+            }
+            if start.elapsed() >= timeout {
+                return Err(io::Error::new(io::ErrorKind::TimedOut, "Read timed out"));
+            }
+            std::thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    /// Discards all bytes received but not read yet.
+    ///
+    /// The default implementation reads until no more data is available.
+    fn discard_input(&mut self) -> io::Result<()> {
+        let mut buf = [0u8; 64];
+        loop {
+            match self.read_with_timeout(&mut buf, Duration::ZERO) {
+                Ok(0) => return Ok(()),
+                Ok(_) => {}
+                Err(e) if matches!(e.kind(), io::ErrorKind::TimedOut | io::ErrorKind::WouldBlock) => return Ok(()),
+                Err(e) => return Err(e),
+            }
+        }
+    }
 }
 
 impl SerialPort for serial2::SerialPort {
@@ -28,12 +79,37 @@ impl SerialPort for serial2::SerialPort {
         // We can just call the inherent method or the trait method.
         io::Write::flush(self)
     }
+
+    fn open_device(path: &Path, baud_rate: u32) -> io::Result<Self> {
+        serial2::SerialPort::open(path, baud_rate)
+    }
+
+    fn read_with_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        // serial2 blocks in read until data arrives or its read timeout expires
+        self.set_read_timeout(timeout)?;
+        io::Read::read(self, buf)
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        self.discard_input_buffer()
+    }
 }
 
 /// A mock serial port for testing.
+#[derive(Default)]
 pub struct MockSerial {
     pub read_buffer: Vec<u8>,
     pub written_data: Vec<u8>,
+    /// Scripted (request, response) pairs, see `expect_exchange`.
+    exchanges: VecDeque<(Vec<u8>, Vec<u8>)>,
+    /// Bytes written since the last completed exchange.
+    pending_write: Vec<u8>,
+    /// Scripted reads served one per `read` call after `read_buffer` is drained.
+    read_chunks: VecDeque<Vec<u8>>,
+    /// Simulated latency before each read returns data.
+    read_delay: Option<Duration>,
+    /// Number of upcoming reads that find no data, see `push_would_block`.
+    would_block: usize,
 }
 
 impl MockSerial {
@@ -41,29 +117,103 @@ impl MockSerial {
         Self {
             read_buffer: Vec::new(),
             written_data: Vec::new(),
+            exchanges: VecDeque::new(),
+            pending_write: Vec::new(),
+            read_chunks: VecDeque::new(),
+            read_delay: None,
+            would_block: 0,
         }
     }
 
+    /// Queues an expected request and the response to serve once it has been written.
+    /// Exchanges are matched in order; writing anything else panics.
+    pub fn expect_exchange(&mut self, request: &[u8], response: &[u8]) {
+        self.exchanges.push_back((request.to_vec(), response.to_vec()));
+    }
+
     pub fn push_read(&mut self, data: &[u8]) {
         self.read_buffer.extend_from_slice(data);
     }
+
+    /// Queues data to be delivered as separate reads, one chunk per `read` call,
+    /// to simulate packets split across several serial reads.
+    pub fn push_read_chunked(&mut self, chunks: Vec<Vec<u8>>) {
+        self.read_chunks.extend(chunks.into_iter().filter(|c| !c.is_empty()));
+    }
+
+    /// Makes the next `count` reads find no data even if some is queued:
+    /// `read` fails with `WouldBlock` and `read_with_timeout` returns 0 bytes,
+    /// like a poll of a port that has nothing yet.
+    pub fn push_would_block(&mut self, count: usize) {
+        self.would_block += count;
+    }
+
+    /// Sleeps for `delay` before each read returns data.
+    pub fn set_read_delay(&mut self, delay: Duration) {
+        self.read_delay = Some(delay);
+    }
+
+    fn has_data(&self) -> bool {
+        !self.read_buffer.is_empty() || !self.read_chunks.is_empty()
+    }
+
+    /// Copies the next available bytes into `buf` without any delay.
+    fn take_read(&mut self, buf: &mut [u8]) -> usize {
+        if !self.read_buffer.is_empty() {
+            let len = std::cmp::min(buf.len(), self.read_buffer.len());
+            buf[..len].copy_from_slice(&self.read_buffer[..len]);
+            self.read_buffer.drain(..len);
+            return len;
+        }
+        let Some(chunk) = self.read_chunks.front_mut() else {
+            return 0;
+        };
+        let len = std::cmp::min(buf.len(), chunk.len());
+        buf[..len].copy_from_slice(&chunk[..len]);
+        chunk.drain(..len);
+        if chunk.is_empty() {
+            self.read_chunks.pop_front();
+        }
+        len
+    }
     
     pub fn pop_write(&mut self) -> Vec<u8> {
         let data = self.written_data.clone();
         self.written_data.clear();
         data
     }
+
+    /// Number of queued bytes that have not been read yet.
+    pub fn remaining_reads(&self) -> usize {
+        self.read_buffer.len() + self.read_chunks.iter().map(Vec::len).sum::<usize>()
+    }
+
+    /// Number of bytes written since the last `pop_write`.
+    pub fn written_len(&self) -> usize {
+        self.written_data.len()
+    }
+
+    /// Panics if queued data was not read or expected exchanges did not happen,
+    /// to check that a test consumed exactly the responses it queued.
+    pub fn assert_all_consumed(&self) {
+        assert_eq!(self.remaining_reads(), 0, "MockSerial: unread data left");
+        assert!(self.exchanges.is_empty(), "MockSerial: {} expected exchanges left", self.exchanges.len());
+    }
 }
 
 impl Read for MockSerial {
     fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
-        if self.read_buffer.is_empty() {
+        if self.would_block > 0 {
+            self.would_block -= 1;
+            return Err(io::Error::new(io::ErrorKind::WouldBlock, "No data yet"));
+        }
+        if !self.has_data() {
             return Err(io::Error::new(io::ErrorKind::WouldBlock, "No data"));
         }
-        let len = std::cmp::min(buf.len(), self.read_buffer.len());
-        buf[..len].copy_from_slice(&self.read_buffer[..len]);
-        self.read_buffer.drain(..len);
-        Ok(len)
+        if let Some(delay) = self.read_delay {
+            std::thread::sleep(delay);
+        }
+        Ok(self.take_read(buf))
     }
 }
 
@@ -72,12 +222,46 @@ impl SerialPort for MockSerial {
     fn flush(&mut self) -> io::Result<()> {
         Ok(())
     }
+
+    fn read_with_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        if self.would_block > 0 {
+            self.would_block -= 1;
+            return Ok(0);
+        }
+        // Nothing will ever arrive on an empty mock, so time out right away
+        if !self.has_data() {
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "No data"));
+        }
+        if let Some(delay) = self.read_delay
+            && delay > timeout
+        {
+            std::thread::sleep(timeout);
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "Read delay exceeds timeout"));
+        }
+        self.read(buf)
+    }
 }
 
 // Fix Write impl for MockSerial
 impl Write for MockSerial {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         self.written_data.extend_from_slice(buf);
+        if !self.exchanges.is_empty() {
+            self.pending_write.extend_from_slice(buf);
+            while let Some((request, _)) = self.exchanges.front() {
+                if self.pending_write.len() < request.len() {
+                    break;
+                }
+                assert_eq!(
+                    &self.pending_write[..request.len()],
+                    &request[..],
+                    "MockSerial: unexpected request"
+                );
+                self.pending_write.drain(..request.len());
+                let (_, response) = self.exchanges.pop_front().unwrap();
+                self.read_buffer.extend_from_slice(&response);
+            }
+        }
         Ok(buf.len())
     }
 
@@ -85,3 +269,511 @@ impl Write for MockSerial {
         Ok(())
     }
 }
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncRead for MockSerial {
+    fn poll_read(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &mut tokio::io::ReadBuf<'_>,
+    ) -> std::task::Poll<io::Result<()>> {
+        // Zero bytes would mean EOF, so an empty read buffer stays pending like an
+        // idle port. Nothing can push data meanwhile, the caller's timeout ends the wait.
+        let this = self.get_mut();
+        let mut data = vec![0u8; buf.remaining()];
+        let len = this.take_read(&mut data);
+        if len == 0 && buf.remaining() > 0 {
+            return std::task::Poll::Pending;
+        }
+        buf.put_slice(&data[..len]);
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(feature = "tokio")]
+impl tokio::io::AsyncWrite for MockSerial {
+    fn poll_write(
+        self: std::pin::Pin<&mut Self>,
+        _cx: &mut std::task::Context<'_>,
+        buf: &[u8],
+    ) -> std::task::Poll<io::Result<usize>> {
+        self.get_mut().written_data.extend_from_slice(buf);
+        std::task::Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: std::pin::Pin<&mut Self>, _cx: &mut std::task::Context<'_>) -> std::task::Poll<io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: std::pin::Pin<&mut Self>, _cx: &mut std::task::Context<'_>) -> std::task::Poll<io::Result<()>> {
+        std::task::Poll::Ready(Ok(()))
+    }
+}
+
+/// Wraps a port and logs every byte written to and read from it, one line
+/// per call: `tx` or `rx` followed by the bytes in hex. Replay the log with `ReplayPort`.
+pub struct RecordingPort<P: SerialPort, W: Write + Send = File> {
+    inner: P,
+    log: W,
+}
+
+impl<P: SerialPort> RecordingPort<P> {
+    /// Records into a new file at `path`.
+    pub fn create(inner: P, path: impl AsRef<Path>) -> io::Result<Self> {
+        Ok(Self::new(inner, File::create(path)?))
+    }
+}
+
+impl<P: SerialPort, W: Write + Send> RecordingPort<P, W> {
+    pub fn new(inner: P, log: W) -> Self {
+        Self { inner, log }
+    }
+
+    /// Returns the wrapped port and the log.
+    pub fn into_inner(self) -> (P, W) {
+        (self.inner, self.log)
+    }
+
+    fn record(&mut self, direction: Direction, bytes: &[u8]) -> io::Result<()> {
+        if bytes.is_empty() {
+            return Ok(());
+        }
+        let tag = match direction {
+            Direction::Tx => "tx",
+            Direction::Rx => "rx",
+        };
+        let hex: Vec<String> = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+        writeln!(self.log, "{} {}", tag, hex.join(" "))
+    }
+}
+
+impl<P: SerialPort, W: Write + Send> Read for RecordingPort<P, W> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let n = self.inner.read(buf)?;
+        self.record(Direction::Rx, &buf[..n])?;
+        Ok(n)
+    }
+}
+
+impl<P: SerialPort, W: Write + Send> Write for RecordingPort<P, W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let n = self.inner.write(buf)?;
+        self.record(Direction::Tx, &buf[..n])?;
+        Ok(n)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::Write::flush(&mut self.inner)?;
+        self.log.flush()
+    }
+}
+
+impl<P: SerialPort, W: Write + Send> SerialPort for RecordingPort<P, W> {
+    fn flush(&mut self) -> io::Result<()> {
+        SerialPort::flush(&mut self.inner)?;
+        self.log.flush()
+    }
+
+    fn read_with_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        let n = self.inner.read_with_timeout(buf, timeout)?;
+        self.record(Direction::Rx, &buf[..n])?;
+        Ok(n)
+    }
+}
+
+/// Serves a session recorded by `RecordingPort`. Writes must match the
+/// recorded requests, and each recorded response only becomes readable once
+/// the request before it was written.
+#[derive(Debug, Default)]
+pub struct ReplayPort {
+    entries: VecDeque<(Direction, Vec<u8>)>,
+}
+
+impl ReplayPort {
+    /// Loads a recording from the file at `path`.
+    pub fn open(path: impl AsRef<Path>) -> io::Result<Self> {
+        Self::from_reader(io::BufReader::new(File::open(path)?))
+    }
+
+    pub fn from_reader(reader: impl BufRead) -> io::Result<Self> {
+        let mut entries = VecDeque::new();
+        for line in reader.lines() {
+            let line = line?;
+            let mut fields = line.split_whitespace();
+            let direction = match fields.next() {
+                Some("tx") => Direction::Tx,
+                Some("rx") => Direction::Rx,
+                None => continue,
+                Some(tag) => return Err(invalid_data(format!("unknown direction {:?}", tag))),
+            };
+            let bytes = fields
+                .map(|hex| u8::from_str_radix(hex, 16).map_err(|e| invalid_data(format!("{:?}: {}", hex, e))))
+                .collect::<io::Result<Vec<u8>>>()?;
+            entries.push_back((direction, bytes));
+        }
+        Ok(Self { entries })
+    }
+
+    /// Whether the whole recording has been written and read.
+    pub fn is_finished(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+impl Read for ReplayPort {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let Some((Direction::Rx, data)) = self.entries.front_mut() else {
+            return Err(io::Error::new(io::ErrorKind::WouldBlock, "No data"));
+        };
+        let n = buf.len().min(data.len());
+        buf[..n].copy_from_slice(&data[..n]);
+        data.drain(..n);
+        if data.is_empty() {
+            self.entries.pop_front();
+        }
+        Ok(n)
+    }
+}
+
+impl Write for ReplayPort {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let mut rest = buf;
+        while !rest.is_empty() {
+            let Some((Direction::Tx, expected)) = self.entries.front_mut() else {
+                return Err(invalid_data(format!("write not in recording: {:02X?}", rest)));
+            };
+            let n = rest.len().min(expected.len());
+            if rest[..n] != expected[..n] {
+                return Err(invalid_data(format!("expected write {:02X?}, got {:02X?}", expected, rest)));
+            }
+            expected.drain(..n);
+            if expected.is_empty() {
+                self.entries.pop_front();
+            }
+            rest = &rest[n..];
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SerialPort for ReplayPort {
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+
+    fn read_with_timeout(&mut self, buf: &mut [u8], _timeout: Duration) -> io::Result<usize> {
+        // Nothing else will arrive, as with an empty MockSerial
+        match self.read(buf) {
+            Err(e) if e.kind() == io::ErrorKind::WouldBlock => {
+                Err(io::Error::new(io::ErrorKind::TimedOut, "No recorded data"))
+            }
+            result => result,
+        }
+    }
+}
+
+enum WriteFault {
+    Error(io::ErrorKind),
+    /// Forward only this many bytes but report the whole buffer as written.
+    Truncate(usize),
+}
+
+/// Wraps a port and makes scheduled reads and writes fail, for testing how
+/// an application copes with a flaky connection. Calls are counted from 1
+/// across `read` and `read_with_timeout`; all other calls are passed through.
+pub struct FaultyPort<P: SerialPort> {
+    inner: P,
+    reads: usize,
+    writes: usize,
+    read_faults: Vec<(usize, io::ErrorKind)>,
+    write_faults: Vec<(usize, WriteFault)>,
+}
+
+impl<P: SerialPort> FaultyPort<P> {
+    pub fn new(inner: P) -> Self {
+        Self { inner, reads: 0, writes: 0, read_faults: Vec::new(), write_faults: Vec::new() }
+    }
+
+    /// Makes the `nth` read fail with an error of `kind` without reading.
+    /// `ErrorKind::TimedOut` surfaces as `Error::Timeout`, other kinds as `Error::Io`.
+    pub fn fail_read(&mut self, nth: usize, kind: io::ErrorKind) {
+        self.read_faults.push((nth, kind));
+    }
+
+    /// Makes the `nth` write fail with an error of `kind` without writing.
+    pub fn fail_write(&mut self, nth: usize, kind: io::ErrorKind) {
+        self.write_faults.push((nth, WriteFault::Error(kind)));
+    }
+
+    /// Makes the `nth` write pass on only the first `len` bytes while reporting
+    /// success, like a frame cut short on the wire.
+    pub fn truncate_write(&mut self, nth: usize, len: usize) {
+        self.write_faults.push((nth, WriteFault::Truncate(len)));
+    }
+
+    /// Number of reads and writes so far.
+    pub fn calls(&self) -> (usize, usize) {
+        (self.reads, self.writes)
+    }
+
+    pub fn get_mut(&mut self) -> &mut P {
+        &mut self.inner
+    }
+
+    pub fn into_inner(self) -> P {
+        self.inner
+    }
+
+    fn next_read_fault(&mut self) -> io::Result<()> {
+        self.reads += 1;
+        match self.read_faults.iter().position(|&(nth, _)| nth == self.reads) {
+            Some(i) => Err(io::Error::new(self.read_faults.remove(i).1, "Injected read fault")),
+            None => Ok(()),
+        }
+    }
+}
+
+impl<P: SerialPort> Read for FaultyPort<P> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.next_read_fault()?;
+        self.inner.read(buf)
+    }
+}
+
+impl<P: SerialPort> Write for FaultyPort<P> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.writes += 1;
+        let fault = self.write_faults.iter().position(|&(nth, _)| nth == self.writes);
+        match fault.map(|i| self.write_faults.remove(i).1) {
+            Some(WriteFault::Error(kind)) => Err(io::Error::new(kind, "Injected write fault")),
+            Some(WriteFault::Truncate(len)) => {
+                self.inner.write_all(&buf[..len.min(buf.len())])?;
+                Ok(buf.len())
+            }
+            None => self.inner.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::Write::flush(&mut self.inner)
+    }
+}
+
+impl<P: SerialPort> SerialPort for FaultyPort<P> {
+    fn flush(&mut self) -> io::Result<()> {
+        SerialPort::flush(&mut self.inner)
+    }
+
+    fn read_with_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        self.next_read_fault()?;
+        self.inner.read_with_timeout(buf, timeout)
+    }
+
+    fn discard_input(&mut self) -> io::Result<()> {
+        self.inner.discard_input()
+    }
+}
+
+/// A controller reached over TCP, e.g. through a socket bridge on the
+/// Raspberry Pi of the robot. The frames are the same as on the serial port.
+pub struct TcpSerial {
+    stream: TcpStream,
+}
+
+impl TcpSerial {
+    pub fn connect(addr: impl ToSocketAddrs) -> io::Result<Self> {
+        Self::from_stream(TcpStream::connect(addr)?)
+    }
+
+    /// Like `connect`, but fails with `ErrorKind::TimedOut` if the connection
+    /// isn't established within `timeout`.
+    pub fn connect_timeout(addr: &SocketAddr, timeout: Duration) -> io::Result<Self> {
+        Self::from_stream(TcpStream::connect_timeout(addr, timeout)?)
+    }
+
+    /// Wraps a connected stream. Disables Nagle's algorithm, as frames are small
+    /// and latency matters.
+    pub fn from_stream(stream: TcpStream) -> io::Result<Self> {
+        stream.set_nodelay(true)?;
+        Ok(Self { stream })
+    }
+
+    /// Sets the timeout of plain `read` calls; requests use their own timeout.
+    pub fn set_read_timeout(&mut self, timeout: Option<Duration>) -> io::Result<()> {
+        self.stream.set_read_timeout(timeout)
+    }
+
+    pub fn into_inner(self) -> TcpStream {
+        self.stream
+    }
+}
+
+impl Read for TcpSerial {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.stream.read(buf)
+    }
+}
+
+impl Write for TcpSerial {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.stream.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+}
+
+impl SerialPort for TcpSerial {
+    fn flush(&mut self) -> io::Result<()> {
+        self.stream.flush()
+    }
+
+    fn read_with_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> io::Result<usize> {
+        // A zero read timeout is rejected by the socket, so only take what's already there
+        let result = if timeout.is_zero() {
+            self.stream.set_nonblocking(true)?;
+            let result = self.stream.read(buf);
+            self.stream.set_nonblocking(false)?;
+            result
+        } else {
+            self.stream.set_read_timeout(Some(timeout))?;
+            self.stream.read(buf)
+        };
+        match result {
+            Err(e) if matches!(e.kind(), io::ErrorKind::WouldBlock | io::ErrorKind::TimedOut) => {
+                Err(io::Error::new(io::ErrorKind::TimedOut, "Read timed out"))
+            }
+            // A socket only reads nothing once the peer has closed it
+            Ok(0) if !buf.is_empty() => Err(io::Error::new(io::ErrorKind::UnexpectedEof, "Connection closed")),
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A port without native timeout support that has data only after a few polls.
+    struct SlowPort {
+        polls_until_data: usize,
+    }
+
+    impl Read for SlowPort {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.polls_until_data > 0 {
+                self.polls_until_data -= 1;
+                return Err(io::Error::new(io::ErrorKind::WouldBlock, "No data"));
+            }
+            buf[0] = 0xAB;
+            Ok(1)
+        }
+    }
+
+    impl Write for SlowPort {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SerialPort for SlowPort {
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_default_read_with_timeout() {
+        let mut port = SlowPort { polls_until_data: 3 };
+        let mut buf = [0u8; 1];
+        assert_eq!(port.read_with_timeout(&mut buf, Duration::from_secs(1)).unwrap(), 1);
+        assert_eq!(buf[0], 0xAB);
+    }
+
+    #[test]
+    fn test_default_read_with_timeout_expires() {
+        let mut port = SlowPort { polls_until_data: usize::MAX };
+        let mut buf = [0u8; 1];
+        let err = port.read_with_timeout(&mut buf, Duration::from_millis(5)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+    }
+
+    #[test]
+    fn test_mock_read_with_timeout() {
+        let mut mock = MockSerial::new();
+        let mut buf = [0u8; 2];
+        let err = mock.read_with_timeout(&mut buf, Duration::from_secs(1)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+
+        mock.push_read(&[1, 2, 3]);
+        assert_eq!(mock.read_with_timeout(&mut buf, Duration::from_secs(1)).unwrap(), 2);
+        assert_eq!(buf, [1, 2]);
+    }
+
+    #[test]
+    fn test_push_read_chunked() {
+        let mut mock = MockSerial::new();
+        mock.push_read_chunked(vec![vec![1, 2], vec![3], vec![4, 5, 6]]);
+
+        let mut buf = [0u8; 8];
+        assert_eq!(mock.read(&mut buf).unwrap(), 2);
+        assert_eq!(&buf[..2], &[1, 2]);
+        assert_eq!(mock.read(&mut buf).unwrap(), 1);
+        assert_eq!(buf[0], 3);
+        assert_eq!(mock.read(&mut buf).unwrap(), 3);
+        assert_eq!(&buf[..3], &[4, 5, 6]);
+        assert_eq!(mock.read(&mut buf).unwrap_err().kind(), io::ErrorKind::WouldBlock);
+    }
+
+    #[test]
+    fn test_read_delay_exceeds_timeout() {
+        let mut mock = MockSerial::new();
+        mock.push_read(&[1]);
+        mock.set_read_delay(Duration::from_millis(20));
+
+        let mut buf = [0u8; 1];
+        let err = mock.read_with_timeout(&mut buf, Duration::from_millis(5)).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::TimedOut);
+        assert_eq!(mock.read_with_timeout(&mut buf, Duration::from_millis(50)).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_remaining_reads() {
+        let mut mock = MockSerial::new();
+        mock.push_read(&[1, 2, 3]);
+        mock.push_read_chunked(vec![vec![4, 5]]);
+        assert_eq!(mock.remaining_reads(), 5);
+
+        let mut buf = [0u8; 4];
+        assert_eq!(mock.read(&mut buf).unwrap(), 3);
+        assert_eq!(mock.remaining_reads(), 2);
+        assert_eq!(mock.read(&mut buf).unwrap(), 2);
+        mock.assert_all_consumed();
+
+        mock.write_all(&[0xFE, 0xFE]).unwrap();
+        assert_eq!(mock.written_len(), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "unread data left")]
+    fn test_assert_all_consumed_leftover() {
+        let mut mock = MockSerial::new();
+        mock.push_read(&[0xFE, 0xFE, 0x02, 0x10, 0xFA]);
+        let mut buf = [0u8; 2];
+        assert_eq!(mock.read(&mut buf).unwrap(), 2);
+        mock.assert_all_consumed();
+    }
+}
@@ -0,0 +1,65 @@
+/*
+ * Copyright (C) 2026 Progress Robotics UG
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Jog session guard that stops the arm when it goes out of scope.
+
+use crate::io::SerialPort;
+use crate::robot::{MyCobot, Result};
+use log::warn;
+
+/// Borrows the robot for jogging and sends `jog_stop` when dropped, so the
+/// arm also stops on early returns and panics. Created by `MyCobot::jog_session`.
+pub struct JogController<'a, P: SerialPort> {
+    robot: &'a mut MyCobot<P>,
+    /// Whether a jog was started since the last stop.
+    jogging: bool,
+}
+
+impl<'a, P: SerialPort> JogController<'a, P> {
+    pub(crate) fn new(robot: &'a mut MyCobot<P>) -> Self {
+        Self { robot, jogging: false }
+    }
+
+    /// See `MyCobot::jog_angle`.
+    pub fn jog_angle(&mut self, joint: u8, direction: i8, speed: u8) -> Result<()> {
+        self.jogging = true;
+        self.robot.jog_angle(joint, direction, speed)
+    }
+
+    /// See `MyCobot::jog_coord`.
+    pub fn jog_coord(&mut self, axis: u8, direction: i8, speed: u8) -> Result<()> {
+        self.jogging = true;
+        self.robot.jog_coord(axis, direction, speed)
+    }
+
+    /// Stops the current jog, keeping the session open.
+    pub fn stop(&mut self) -> Result<()> {
+        self.robot.jog_stop()?;
+        self.jogging = false;
+        Ok(())
+    }
+}
+
+impl<P: SerialPort> Drop for JogController<'_, P> {
+    fn drop(&mut self) {
+        if self.jogging
+            && let Err(e) = self.robot.jog_stop()
+        {
+            warn!("Failed to stop jog on drop: {}", e);
+        }
+    }
+}
@@ -0,0 +1,133 @@
+/*
+ * Copyright (C) 2026 Progress Robotics UG
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Offline kinematics, computed on the host without asking the controller.
+
+use crate::model::RobotModel;
+use crate::types::{Coords, JointAngles};
+use std::f64::consts::FRAC_PI_2;
+
+/// Standard DH parameters of one joint: (theta offset in radians, d in mm, a in mm, alpha in radians).
+type DhJoint = (f64, f64, f64, f64);
+
+/// Published DH parameters of the MyCobot 280, joint 1 first.
+const MYCOBOT_280_DH: [DhJoint; 6] = [
+    (0.0, 131.22, 0.0, FRAC_PI_2),
+    (-FRAC_PI_2, 0.0, -110.4, 0.0),
+    (0.0, 0.0, -96.0, 0.0),
+    (-FRAC_PI_2, 63.4, 0.0, FRAC_PI_2),
+    (FRAC_PI_2, 75.05, 0.0, -FRAC_PI_2),
+    (0.0, 45.6, 0.0, 0.0),
+];
+
+type Matrix = [[f64; 4]; 4];
+
+const IDENTITY: Matrix = [
+    [1.0, 0.0, 0.0, 0.0],
+    [0.0, 1.0, 0.0, 0.0],
+    [0.0, 0.0, 1.0, 0.0],
+    [0.0, 0.0, 0.0, 1.0],
+];
+
+fn multiply(a: &Matrix, b: &Matrix) -> Matrix {
+    let mut result = [[0.0; 4]; 4];
+    for (i, row) in result.iter_mut().enumerate() {
+        for (j, value) in row.iter_mut().enumerate() {
+            *value = (0..4).map(|k| a[i][k] * b[k][j]).sum();
+        }
+    }
+    result
+}
+
+fn dh_transform((offset, d, a, alpha): DhJoint, angle: f64) -> Matrix {
+    let (st, ct) = (angle + offset).sin_cos();
+    let (sa, ca) = alpha.sin_cos();
+    [
+        [ct, -st * ca, st * sa, a * ct],
+        [st, ct * ca, -ct * sa, a * st],
+        [0.0, sa, ca, d],
+        [0.0, 0.0, 0.0, 1.0],
+    ]
+}
+
+/// Computes the tool pose for joint angles in degrees, like `get_coords`
+/// would report it: position in millimeters and Rx, Ry, Rz as roll, pitch
+/// and yaw in degrees.
+///
+/// Returns `None` for models whose DH parameters aren't included yet; only
+/// the MyCobot 280 is supported.
+pub fn forward_kinematics(angles: &JointAngles, model: RobotModel) -> Option<Coords> {
+    let dh = match model {
+        RobotModel::MyCobot280 => MYCOBOT_280_DH,
+        _ => return None,
+    };
+
+    let angles: [f32; 6] = (*angles).into();
+    let mut t = IDENTITY;
+    for (&joint, &angle) in dh.iter().zip(&angles) {
+        t = multiply(&t, &dh_transform(joint, (angle as f64).to_radians()));
+    }
+
+    let rx = t[2][1].atan2(t[2][2]);
+    let ry = (-t[2][0]).atan2(t[0][0].hypot(t[1][0]));
+    let rz = t[1][0].atan2(t[0][0]);
+    Some(Coords {
+        x: t[0][3] as f32,
+        y: t[1][3] as f32,
+        z: t[2][3] as f32,
+        rx: rx.to_degrees() as f32,
+        ry: ry.to_degrees() as f32,
+        rz: rz.to_degrees() as f32,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: Coords, expected: [f32; 6]) {
+        let actual: [f32; 6] = actual.into();
+        for (a, e) in actual.iter().zip(&expected) {
+            assert!((a - e).abs() < 0.01, "{:?} != {:?}", actual, expected);
+        }
+    }
+
+    #[test]
+    fn test_forward_kinematics_home() {
+        let coords = forward_kinematics(&JointAngles::default(), RobotModel::MyCobot280).unwrap();
+        // Arm pointing straight up, at the top of the workspace
+        assert_close(coords, [45.6, -63.4, 412.67, -90.0, 0.0, -90.0]);
+    }
+
+    #[test]
+    fn test_forward_kinematics_poses() {
+        // Base rotated by 90 degrees
+        let angles = JointAngles::from([90.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+        let coords = forward_kinematics(&angles, RobotModel::MyCobot280).unwrap();
+        assert_close(coords, [63.4, 45.6, 412.67, -90.0, 0.0, 0.0]);
+
+        // Arm stretched out horizontally, at the edge of the workspace
+        let angles = JointAngles::from([0.0, -90.0, 0.0, 0.0, 0.0, 0.0]);
+        let coords = forward_kinematics(&angles, RobotModel::MyCobot280).unwrap();
+        assert_close(coords, [281.45, -63.4, 85.62, -180.0, 0.0, -90.0]);
+    }
+
+    #[test]
+    fn test_forward_kinematics_unsupported_model() {
+        assert!(forward_kinematics(&JointAngles::default(), RobotModel::MechArm270).is_none());
+    }
+}
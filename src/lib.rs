@@ -15,16 +15,55 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
+#![cfg_attr(not(any(test, feature = "std")), no_std)]
+
+//! Without the default `std` feature only the protocol, command and model
+//! definitions are available, e.g. to frame packets on a microcontroller.
+//! This is a partial step: the `MyCobot` driver itself still needs `std`,
+//! there are no `core`-only serial and clock traits or `embedded-hal` backend yet.
+
+#[cfg(not(feature = "std"))]
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod clock;
+#[cfg(feature = "std")]
 pub mod io;
 pub mod protocol;
 pub mod commands;
+#[cfg(feature = "std")]
 pub mod robot;
+#[cfg(feature = "std")]
+pub mod builder;
+#[cfg(feature = "std")]
+pub mod jog;
+pub mod types;
+pub mod model;
+#[cfg(feature = "std")]
+pub mod kinematics;
+#[cfg(feature = "tokio")]
+pub mod async_robot;
 
-pub use io::{SerialPort, MockSerial};
-pub use robot::{MyCobot, Error, Result};
-pub use commands::Command;
+#[cfg(feature = "std")]
+pub use clock::{Clock, FakeClock, SystemClock};
+#[cfg(feature = "std")]
+pub use io::{SerialPort, FaultyPort, MockSerial, RecordingPort, ReplayPort, TcpSerial};
+#[cfg(feature = "std")]
+pub use robot::{pin_state, MyCobot, Error, FrameObserver, Result, RetryPolicy, RobotError, RobotStatus, ATOM_GPIO_PINS};
+#[cfg(feature = "std")]
+pub use builder::MyCobotBuilder;
+#[cfg(feature = "std")]
+pub use jog::JogController;
+pub use types::{AngleUnit, Axis, ClampMode, Color, Coords, JointAngles, ServoStatus};
+#[cfg(feature = "std")]
+pub use kinematics::forward_kinematics;
+pub use model::{RobotModel, MYCOBOT_280_ANGLE_LIMITS, MYCOBOT_280_COORD_LIMITS};
+pub use commands::{Command, GripperType, MoveMode, PinMode};
+pub use protocol::{Direction, FrameError};
+#[cfg(feature = "tokio")]
+pub use async_robot::AsyncMyCobot;
 
-#[cfg(test)]
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -34,12 +73,19 @@ mod tests {
         let mut robot = MyCobot::new(mock);
         
         robot.power_on().unwrap();
-        
-        // We need to access the mock inside the robot to verify writes.
-        // But MyCobot consumes the port.
-        // We can't access `port` field because it's private.
-        // We should add a method to decompose or access inner? 
-        // Or make MockSerial split into verified channels.
-        // For now, let's just make `port` public for crate or provide a `into_inner`.
+
+        let written = robot.into_inner().pop_write();
+        assert_eq!(written, vec![0xFE, 0xFE, 0x02, 0x10, 0xFA]);
+    }
+
+    #[test]
+    fn test_port_mut() {
+        let mut robot = MyCobot::new(MockSerial::new());
+
+        robot.power_off().unwrap();
+        assert_eq!(robot.port_mut().pop_write(), vec![0xFE, 0xFE, 0x02, 0x11, 0xFA]);
+
+        robot.power_on().unwrap();
+        assert_eq!(robot.port_mut().pop_write(), vec![0xFE, 0xFE, 0x02, 0x10, 0xFA]);
     }
 }
@@ -0,0 +1,180 @@
+/*
+ * Copyright (C) 2026 Progress Robotics UG
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+//! Per-model parameters of the supported Elephant Robotics arms.
+
+/// Joint angle limits of the MyCobot 280 in degrees, joint 1 first.
+pub const MYCOBOT_280_ANGLE_LIMITS: [(f32, f32); 6] = [
+    (-168.0, 168.0),
+    (-135.0, 135.0),
+    (-150.0, 150.0),
+    (-145.0, 145.0),
+    (-165.0, 165.0),
+    (-180.0, 180.0),
+];
+
+/// Cartesian workspace of the MyCobot 280: X, Y, Z in millimeters, Rx, Ry, Rz in degrees.
+pub const MYCOBOT_280_COORD_LIMITS: [(f32, f32); 6] = [
+    (-281.45, 281.45),
+    (-281.45, 281.45),
+    (-70.0, 412.67),
+    (-180.0, 180.0),
+    (-180.0, 180.0),
+    (-180.0, 180.0),
+];
+
+/// The arm connected to the port. Selects the number of joints, the
+/// fixed-point scaling of angles and coordinates and the default limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum RobotModel {
+    #[default]
+    MyCobot280,
+    MyCobotPro320,
+    MechArm270,
+    /// 4 joints. Coordinates are `[x, y, z, theta]`, stored in the first
+    /// four slots of the 6-element arrays used throughout the API.
+    MyPalletizer260,
+}
+
+impl RobotModel {
+    /// Number of joints, and of values in angle and coordinate frames.
+    pub fn dof(self) -> usize {
+        match self {
+            RobotModel::MyPalletizer260 => 4,
+            _ => 6,
+        }
+    }
+
+    /// Angles are sent in hundredths of a degree.
+    pub fn angle_scale(self) -> f32 {
+        100.0
+    }
+
+    /// X, Y and Z are sent in tenths of a millimeter.
+    pub fn position_scale(self) -> f32 {
+        10.0
+    }
+
+    /// Rotations (Rx, Ry, Rz or theta) are sent in hundredths of a degree.
+    pub fn rotation_scale(self) -> f32 {
+        100.0
+    }
+
+    /// Default (min, max) angle of each joint in degrees.
+    /// Joints the model doesn't have are limited to 0.
+    pub fn angle_limits(self) -> [(f32, f32); 6] {
+        match self {
+            RobotModel::MyCobot280 => MYCOBOT_280_ANGLE_LIMITS,
+            RobotModel::MyCobotPro320 => [
+                (-170.0, 170.0),
+                (-137.0, 137.0),
+                (-151.0, 142.0),
+                (-148.0, 148.0),
+                (-169.0, 169.0),
+                (-180.0, 180.0),
+            ],
+            RobotModel::MechArm270 => [
+                (-165.0, 165.0),
+                (-90.0, 90.0),
+                (-180.0, 65.0),
+                (-165.0, 165.0),
+                (-115.0, 115.0),
+                (-175.0, 175.0),
+            ],
+            RobotModel::MyPalletizer260 => [
+                (-162.0, 162.0),
+                (-2.0, 90.0),
+                (-8.0, 60.0),
+                (-180.0, 180.0),
+                (0.0, 0.0),
+                (0.0, 0.0),
+            ],
+        }
+    }
+
+    /// Joint angles of the home pose in degrees. All supported models are
+    /// calibrated with every joint at zero, so this is the same for each.
+    pub fn home_angles(self) -> [f32; 6] {
+        [0.0; 6]
+    }
+
+    /// Default (min, max) of each Cartesian axis, millimeters and degrees.
+    /// Axes the model doesn't have are limited to 0.
+    pub fn coord_limits(self) -> [(f32, f32); 6] {
+        match self {
+            RobotModel::MyCobot280 => MYCOBOT_280_COORD_LIMITS,
+            RobotModel::MyCobotPro320 => [
+                (-350.0, 350.0),
+                (-350.0, 350.0),
+                (-41.0, 523.9),
+                (-180.0, 180.0),
+                (-180.0, 180.0),
+                (-180.0, 180.0),
+            ],
+            RobotModel::MechArm270 => [
+                (-272.0, 272.0),
+                (-272.0, 272.0),
+                (-36.0, 408.9),
+                (-180.0, 180.0),
+                (-180.0, 180.0),
+                (-180.0, 180.0),
+            ],
+            RobotModel::MyPalletizer260 => [
+                (-260.0, 260.0),
+                (-260.0, 260.0),
+                (-15.0, 357.58),
+                (-180.0, 180.0),
+                (0.0, 0.0),
+                (0.0, 0.0),
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dof() {
+        assert_eq!(RobotModel::MyCobot280.dof(), 6);
+        assert_eq!(RobotModel::MyCobotPro320.dof(), 6);
+        assert_eq!(RobotModel::MechArm270.dof(), 6);
+        assert_eq!(RobotModel::MyPalletizer260.dof(), 4);
+    }
+
+    #[test]
+    fn test_unused_joints_are_locked() {
+        let limits = RobotModel::MyPalletizer260.angle_limits();
+        assert_eq!(&limits[4..], &[(0.0, 0.0), (0.0, 0.0)]);
+    }
+
+    #[test]
+    fn test_home_within_limits() {
+        let models = [
+            RobotModel::MyCobot280,
+            RobotModel::MyCobotPro320,
+            RobotModel::MechArm270,
+            RobotModel::MyPalletizer260,
+        ];
+        for model in models {
+            for (angle, (min, max)) in model.home_angles().into_iter().zip(model.angle_limits()) {
+                assert!((min..=max).contains(&angle), "{:?}", model);
+            }
+        }
+    }
+}
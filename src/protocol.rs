@@ -16,38 +16,248 @@
  */
 
 use crate::commands::Command;
+#[cfg(not(feature = "std"))]
+use alloc::{format, string::String, vec::Vec};
 
 pub const HEADER: [u8; 2] = [0xFE, 0xFE];
 pub const FOOTER: u8 = 0xFA;
+/// Length byte announcing a two-byte length field, see `Packet::extended_length`.
+pub const EXTENDED_LENGTH: u8 = 0x00;
+
+/// Why a packet couldn't be framed or parsed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameError {
+    /// `Command::Undefined` (0x00) is never sent on purpose.
+    UndefinedCommand,
+    /// The payload is too long for the length field, see [`Packet::fits`].
+    PayloadTooLong(usize),
+    /// The data doesn't start with [`HEADER`].
+    InvalidHeader,
+    /// The length field is too small for a frame.
+    InvalidLength,
+    /// The frame doesn't end with [`FOOTER`].
+    InvalidFooter,
+    /// The checksum byte doesn't match the command and payload.
+    ChecksumMismatch,
+    /// The data ends before the frame does.
+    Incomplete,
+    /// Bytes follow the frame where none were expected.
+    TrailingBytes(usize),
+}
+
+impl core::fmt::Display for FrameError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            FrameError::UndefinedCommand => write!(f, "Command::Undefined cannot be sent"),
+            FrameError::PayloadTooLong(len) => write!(f, "payload of {} bytes does not fit the length field", len),
+            FrameError::InvalidHeader => write!(f, "Invalid header"),
+            FrameError::InvalidLength => write!(f, "Invalid length"),
+            FrameError::InvalidFooter => write!(f, "Invalid footer"),
+            FrameError::ChecksumMismatch => write!(f, "Invalid checksum"),
+            FrameError::Incomplete => write!(f, "Incomplete frame"),
+            FrameError::TrailingBytes(len) => write!(f, "{} trailing bytes", len),
+        }
+    }
+}
+
+/// Whether a frame was sent to or received from the robot.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Tx,
+    Rx,
+}
 
 #[derive(Debug, Clone, PartialEq)]
 pub struct Packet {
     pub command: Command,
     pub payload: Vec<u8>,
+    /// Whether a checksum byte is placed before the footer.
+    /// The stock firmware does not use one, so this is off by default.
+    pub checksum: bool,
+    /// Whether the frame may use the extended length field needed for long
+    /// payloads: a 0x00 marker in place of the length byte, followed by the
+    /// length as big-endian `u16`. Only newer controllers understand it.
+    pub extended_length: bool,
 }
 
 impl Packet {
     pub fn new(command: Command, payload: Vec<u8>) -> Self {
-        Self { command, payload }
+        Self { command, payload, checksum: false, extended_length: false }
+    }
+
+    /// Creates a packet that is framed with a one-byte checksum before the footer.
+    pub fn with_checksum(command: Command, payload: Vec<u8>) -> Self {
+        Self { command, payload, checksum: true, extended_length: false }
+    }
+
+    /// The command as it appears on the wire.
+    pub fn command_byte(&self) -> u8 {
+        self.command.into()
+    }
+
+    /// Wrapping sum over the command byte and the payload.
+    pub fn compute_checksum(command: u8, payload: &[u8]) -> u8 {
+        payload.iter().fold(command, |acc, &b| acc.wrapping_add(b))
+    }
+
+    /// Length field value: command (1) + payload (N) + [checksum (1)] + footer (1).
+    fn frame_len(&self) -> usize {
+        1 + self.payload.len() + self.checksum as usize + 1
+    }
+
+    /// Whether the payload fits the frame, using the extended length field if enabled.
+    pub fn fits(&self) -> bool {
+        let max = if self.extended_length { u16::MAX as usize } else { u8::MAX as usize };
+        self.frame_len() <= max
     }
 
+    /// Frames the packet, for packets known to be valid such as fixed
+    /// requests. Use [`Packet::try_to_bytes`] for anything built from input.
+    ///
+    /// # Panics
+    ///
+    /// If framing fails, see [`Packet::try_to_bytes`].
     pub fn to_bytes(&self) -> Vec<u8> {
+        self.try_to_bytes().unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Frames the packet. The one-byte length field is used whenever the
+    /// payload fits, the extended one only for longer payloads. Fails for
+    /// `Command::Undefined` and payloads that don't fit.
+    pub fn try_to_bytes(&self) -> Result<Vec<u8>, FrameError> {
         let mut bytes = Vec::new();
+        self.encode_into(&mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// Like [`Packet::try_to_bytes`], but clears and reuses `bytes` to avoid
+    /// an allocation per frame. `bytes` is left untouched on error.
+    pub fn encode_into(&self, bytes: &mut Vec<u8>) -> Result<(), FrameError> {
+        if self.command == Command::Undefined {
+            return Err(FrameError::UndefinedCommand);
+        }
+        if !self.fits() {
+            return Err(FrameError::PayloadTooLong(self.payload.len()));
+        }
+        bytes.clear();
         bytes.extend_from_slice(&HEADER);
-        // Length = command (1) + payload (N) + footer (1)
-        let len = 1 + self.payload.len() as u8 + 1;
-        bytes.push(len);
-        bytes.push(self.command.into());
+        let len = self.frame_len();
+        if len <= u8::MAX as usize {
+            bytes.push(len as u8);
+        } else {
+            bytes.push(EXTENDED_LENGTH);
+            bytes.extend_from_slice(&(len as u16).to_be_bytes());
+        }
+        let command = self.command.into();
+        bytes.push(command);
         bytes.extend_from_slice(&self.payload);
+        if self.checksum {
+            bytes.push(Self::compute_checksum(command, &self.payload));
+        }
         bytes.push(FOOTER);
-        bytes
+        Ok(())
+    }
+
+    /// Returns the offset of the first header in `buffer`, if any.
+    /// In a run of sync bytes the header is taken to be the last two,
+    /// so a stray `0xFE` right before a frame is skipped as well.
+    pub fn find_header(buffer: &[u8]) -> Option<usize> {
+        let start = buffer.windows(2).position(|w| w == HEADER)?;
+        let run = buffer[start..].iter().take_while(|&&b| b == HEADER[0]).count();
+        Some(start + run - 2)
     }
 
     /// Tries to parse a packet from the given buffer.
     /// Returns Ok(Some((packet, bytes_consumed))) if a full packet is found.
     /// Returns Ok(None) if more data is needed.
     /// Returns Err if the data is invalid (e.g. wrong header) and should be skipped.
-    pub fn parse(buffer: &[u8]) -> Result<Option<(Packet, usize)>, String> {
+    pub fn parse(buffer: &[u8]) -> Result<Option<(Packet, usize)>, FrameError> {
+        Self::parse_frame(buffer, false, false)
+    }
+
+    /// Parses a buffer that holds exactly one frame, e.g. what a test port
+    /// recorded for a single command. Unlike [`Packet::parse`], an incomplete
+    /// frame or trailing bytes are an error, and both length forms are accepted.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Packet, FrameError> {
+        match Self::parse_frame(bytes, false, true)? {
+            Some((packet, consumed)) if consumed == bytes.len() => Ok(packet),
+            Some((_, consumed)) => Err(FrameError::TrailingBytes(bytes.len() - consumed)),
+            None => Err(FrameError::Incomplete),
+        }
+    }
+
+    /// Like [`Packet::parse`], but expects and verifies a checksum byte before the footer.
+    pub fn parse_with_checksum(buffer: &[u8]) -> Result<Option<(Packet, usize)>, FrameError> {
+        Self::parse_frame(buffer, true, false)
+    }
+
+    /// Like [`Packet::parse`], but also accepts the extended length field.
+    /// Only use this on links that send long frames: otherwise a noise `FE FE 00`
+    /// would hold back every following frame until up to 64 KiB have arrived.
+    pub fn parse_extended(buffer: &[u8], checksum: bool) -> Result<Option<(Packet, usize)>, FrameError> {
+        Self::parse_frame(buffer, checksum, true)
+    }
+
+    /// Renders the packet for logs, decoding the payload of known responses:
+    /// angles and coordinates in degrees and millimeters, flags as true/false.
+    /// Other payloads are shown as hex.
+    pub fn describe(&self) -> String {
+        let values = |scale: f32| -> Vec<String> {
+            self.payload
+                .chunks_exact(2)
+                .map(|pair| format!("{:.2}", decode_i16_be(pair) as f32 / scale))
+                .collect()
+        };
+        let even = !self.payload.is_empty() && self.payload.len().is_multiple_of(2);
+        match (self.command, self.payload.as_slice()) {
+            (command, []) => format!("{:?}", command),
+            (Command::GetAngles, _) if even => {
+                format!("GetAngles: [{}] deg", values(100.0).join(", "))
+            }
+            (Command::GetCoords, _) if even && self.payload.len() >= 6 => {
+                let position = values(10.0);
+                let rotation = values(100.0);
+                format!(
+                    "GetCoords: [{}] mm, [{}] deg",
+                    position[..3].join(", "),
+                    rotation[3..].join(", ")
+                )
+            }
+            (
+                command @ (Command::IsPoweredOn
+                | Command::IsControllerConnected
+                | Command::IsFreeMoveMode
+                | Command::IsProgramPaused
+                | Command::IsInPosition
+                | Command::CheckRunning
+                | Command::IsGripperMoving
+                | Command::GetDigitalIn
+                | Command::GetBasicIn),
+                &[flag @ (0 | 1)],
+            ) => format!("{:?}: {}", command, flag == 1),
+            (command, payload) => format!("{:?}: {:02X?}", command, payload),
+        }
+    }
+
+    /// Parses every packet in `buffer`, e.g. a captured serial dump, skipping
+    /// bytes that don't start a valid frame. Returns the packets and the number
+    /// of bytes consumed; a trailing partial frame is left unconsumed.
+    pub fn parse_all(buffer: &[u8]) -> (Vec<Packet>, usize) {
+        let mut packets = Vec::new();
+        let mut offset = 0;
+        loop {
+            match Self::parse(&buffer[offset..]) {
+                Ok(Some((packet, consumed))) => {
+                    packets.push(packet);
+                    offset += consumed;
+                }
+                Ok(None) => return (packets, offset),
+                Err(_) => offset += 1,
+            }
+        }
+    }
+
+    fn parse_frame(buffer: &[u8], checksum: bool, allow_extended: bool) -> Result<Option<(Packet, usize)>, FrameError> {
         if buffer.len() < 2 {
             return Ok(None);
         }
@@ -56,16 +266,31 @@ impl Packet {
         if buffer[0] != HEADER[0] || buffer[1] != HEADER[1] {
             // If not starting with header, we should skip one byte to try to find sync
             // But the caller needs to handle skipping. We just say "not a packet at pos 0"
-            return Err("Invalid header".to_string());
+            return Err(FrameError::InvalidHeader);
         }
 
         if buffer.len() < 3 {
             return Ok(None); // Need length byte
         }
 
-        let len_field = buffer[2];
-        let total_frame_len = 2 + 1 + len_field as usize; // Header (2) + LenByte (1) + Body (Length)
-        
+        // Extended frames carry a 0x00 marker followed by a two-byte length,
+        // without them a zero length byte is invalid
+        let extended = allow_extended && buffer[2] == EXTENDED_LENGTH;
+        let (len_field, body_start) = if extended {
+            if buffer.len() < 5 {
+                return Ok(None);
+            }
+            (u16::from_be_bytes([buffer[3], buffer[4]]) as usize, 5)
+        } else {
+            (buffer[2] as usize, 3)
+        };
+        // Command (1) + [checksum (1)] + footer (1)
+        let overhead = 2 + checksum as usize;
+        if len_field < overhead {
+            return Err(FrameError::InvalidLength);
+        }
+        let total_frame_len = body_start + len_field; // Header, length field(s), body
+
         // Wait for full frame
         if buffer.len() < total_frame_len {
             return Ok(None);
@@ -73,23 +298,42 @@ impl Packet {
 
         // Validate footer
         if buffer[total_frame_len - 1] != FOOTER {
-            return Err("Invalid footer".to_string());
+            return Err(FrameError::InvalidFooter);
         }
 
-        let command_byte = buffer[3];
-        let payload_len = len_field as usize - 2; // -1 for command, -1 for footer
-        let payload = buffer[4..4+payload_len].to_vec();
+        let command_byte = buffer[body_start];
+        let payload_len = len_field - overhead;
+        let payload = buffer[body_start + 1..body_start + 1 + payload_len].to_vec();
+
+        if checksum {
+            let expected = Self::compute_checksum(command_byte, &payload);
+            if buffer[body_start + 1 + payload_len] != expected {
+                return Err(FrameError::ChecksumMismatch);
+            }
+        }
 
         Ok(Some((
             Packet {
                 command: Command::from(command_byte),
                 payload,
+                checksum,
+                extended_length: extended,
             },
             total_frame_len
         )))
     }
 }
 
+/// Decodes a big-endian `i16` from the first two bytes of `bytes`.
+pub fn decode_i16_be(bytes: &[u8]) -> i16 {
+    i16::from_be_bytes([bytes[0], bytes[1]])
+}
+
+/// Encodes `value` as big-endian, the byte order of all values on the wire.
+pub fn encode_i16_be(value: i16) -> [u8; 2] {
+    value.to_be_bytes()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -110,4 +354,186 @@ mod tests {
         assert_eq!(packet.command, Command::GetAngles);
         assert_eq!(packet.payload.len(), 0);
     }
+
+    #[test]
+    fn test_from_bytes() {
+        let packet = Packet::from_bytes(&[0xFE, 0xFE, 0x04, 0x6A, 0x01, 0x02, 0xFA]).unwrap();
+        assert_eq!(packet, Packet::new(Command::SetLedRgb, vec![0x01, 0x02]));
+        assert_eq!(packet.command_byte(), 0x6A);
+
+        assert!(Packet::from_bytes(&[]).is_err());
+        assert!(Packet::from_bytes(&[0xFE, 0xFE, 0x02, 0x20]).is_err());
+        assert!(Packet::from_bytes(&[0x00, 0xFE, 0xFE, 0x02, 0x20, 0xFA]).is_err());
+        assert!(Packet::from_bytes(&[0xFE, 0xFE, 0x02, 0x20, 0xFA, 0xFE, 0xFE, 0x02, 0x20, 0xFA]).is_err());
+    }
+
+    #[test]
+    fn test_try_to_bytes_rejects_undefined() {
+        let packet = Packet::new(Command::Undefined, vec![]);
+        assert_eq!(packet.try_to_bytes(), Err(FrameError::UndefinedCommand));
+
+        let mut bytes = vec![0x01];
+        assert!(packet.encode_into(&mut bytes).is_err());
+        assert_eq!(bytes, vec![0x01]);
+    }
+
+    #[test]
+    fn test_to_bytes_allows_unknown() {
+        let bytes = Packet::new(Command::Unknown(0xB0), vec![0x01]).to_bytes();
+        assert_eq!(bytes, vec![0xFE, 0xFE, 0x03, 0xB0, 0x01, 0xFA]);
+    }
+
+    #[test]
+    fn test_encode_into_reuses_buffer() {
+        let long = Packet::new(Command::WriteAngles, vec![0x12; 13]);
+        let short = Packet::new(Command::PowerOn, vec![]);
+        let checked = Packet::with_checksum(Command::SetLedRgb, vec![0x10, 0x20, 0x30]);
+
+        let mut bytes = Vec::new();
+        for packet in [&long, &short, &checked, &long] {
+            packet.encode_into(&mut bytes).unwrap();
+            assert_eq!(bytes, packet.to_bytes());
+        }
+    }
+
+    #[test]
+    fn test_checksum_round_trip() {
+        let packet = Packet::with_checksum(Command::SetLedRgb, vec![0x10, 0x20, 0x30]);
+        let bytes = packet.to_bytes();
+        // Checksum = 0x6A + 0x10 + 0x20 + 0x30 = 0xCA
+        assert_eq!(bytes, vec![0xFE, 0xFE, 0x06, 0x6A, 0x10, 0x20, 0x30, 0xCA, 0xFA]);
+
+        let (parsed, consumed) = Packet::parse_with_checksum(&bytes).unwrap().unwrap();
+        assert_eq!(consumed, bytes.len());
+        assert_eq!(parsed, packet);
+    }
+
+    #[test]
+    fn test_checksum_mismatch() {
+        let mut bytes = Packet::with_checksum(Command::SetLedRgb, vec![0x10, 0x20, 0x30]).to_bytes();
+        bytes[5] ^= 0x01;
+        assert_eq!(Packet::parse_with_checksum(&bytes), Err(FrameError::ChecksumMismatch));
+    }
+
+    #[test]
+    fn test_parse_rejects_short_length() {
+        let data = vec![0xFE, 0xFE, 0x01, 0xFA];
+        assert!(Packet::parse(&data).is_err());
+    }
+
+    #[test]
+    fn test_find_header() {
+        assert_eq!(Packet::find_header(&[0xFE, 0xFE, 0x02, 0x20, 0xFA]), Some(0));
+        assert_eq!(Packet::find_header(&[0x00, 0x13, 0xFE, 0xFE, 0x02]), Some(2));
+        assert_eq!(Packet::find_header(&[0xFE, 0xFE, 0xFE, 0x02]), Some(1));
+        assert_eq!(Packet::find_header(&[0x01, 0xFE, 0x02, 0xFE]), None);
+        assert_eq!(Packet::find_header(&[]), None);
+    }
+
+    #[test]
+    fn test_i16_be() {
+        assert_eq!(decode_i16_be(&[0x00, 0x64]), 100);
+        assert_eq!(decode_i16_be(&[0xFF, 0x9C]), -100);
+        // -90.00 degrees in hundredths
+        assert_eq!(encode_i16_be(-9000), [0xDC, 0xD8]);
+        assert_eq!(decode_i16_be(&[0xDC, 0xD8]), -9000);
+        assert_eq!(encode_i16_be(i16::MIN), [0x80, 0x00]);
+        assert_eq!(decode_i16_be(&[0x7F, 0xFF, 0x00]), i16::MAX);
+    }
+
+    #[test]
+    fn test_i16_be_low_byte_high_bit() {
+        // Low bytes >= 0x80 must not leak into the high byte
+        assert_eq!(decode_i16_be(&[0xFF, 0x80]), -128);
+        assert_eq!(decode_i16_be(&[0x00, 0x80]), 128);
+        assert_eq!(decode_i16_be(&[0xCF, 0xC7]), -12345);
+        for value in [-4567, -128, 128, -12345, 0x7F80] {
+            assert_eq!(decode_i16_be(&encode_i16_be(value)), value);
+        }
+    }
+
+    #[test]
+    fn test_parse_all_back_to_back() {
+        let data = [0xFE, 0xFE, 0x02, 0x10, 0xFA, 0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA];
+        let (packets, consumed) = Packet::parse_all(&data);
+        assert_eq!(
+            packets,
+            vec![Packet::new(Command::PowerOn, vec![]), Packet::new(Command::IsPoweredOn, vec![0x01])]
+        );
+        assert_eq!(consumed, data.len());
+    }
+
+    #[test]
+    fn test_parse_all_with_garbage() {
+        let data = [
+            0x00, 0xFA, 0xFE, 0xFE, 0x02, 0x10, 0xFA, // garbage, PowerOn
+            0xFE, 0x13, 0xFE, 0xFE, 0x02, 0x11, 0xFA, // garbage, PowerOff
+            0xFE, 0xFE, 0x03, 0x12, // partial IsPoweredOn
+        ];
+        let (packets, consumed) = Packet::parse_all(&data);
+        assert_eq!(packets, vec![Packet::new(Command::PowerOn, vec![]), Packet::new(Command::PowerOff, vec![])]);
+        assert_eq!(consumed, 14);
+        assert_eq!(Packet::parse_all(&[]), (vec![], 0));
+    }
+
+    #[test]
+    fn test_describe() {
+        let mut payload = vec![0x00, 0x64, 0xEE, 0x29];
+        payload.extend_from_slice(&[0; 8]);
+        let angles = Packet::new(Command::GetAngles, payload.clone());
+        assert_eq!(angles.describe(), "GetAngles: [1.00, -45.67, 0.00, 0.00, 0.00, 0.00] deg");
+
+        let coords = Packet::new(Command::GetCoords, payload);
+        assert_eq!(coords.describe(), "GetCoords: [10.00, -456.70, 0.00] mm, [0.00, 0.00, 0.00] deg");
+
+        assert_eq!(Packet::new(Command::IsPoweredOn, vec![1]).describe(), "IsPoweredOn: true");
+        assert_eq!(Packet::new(Command::PowerOn, vec![]).describe(), "PowerOn");
+    }
+
+    #[test]
+    fn test_describe_unknown() {
+        let packet = Packet::new(Command::Unknown(0x7F), vec![0x05, 0xAB]);
+        assert_eq!(packet.describe(), "Unknown(127): [05, AB]");
+    }
+
+    #[test]
+    fn test_extended_length_round_trip() {
+        let payload: Vec<u8> = (0..300).map(|i| i as u8).collect();
+        let mut packet = Packet::new(Command::GetEncoders, payload);
+        assert!(!packet.fits());
+        packet.extended_length = true;
+        assert!(packet.fits());
+
+        let bytes = packet.to_bytes();
+        // Length 302 = 0x012E after the 0x00 marker
+        assert_eq!(&bytes[..6], &[0xFE, 0xFE, 0x00, 0x01, 0x2E, 0x3D]);
+        assert_eq!(bytes.len(), 5 + 302);
+
+        let (parsed, consumed) = Packet::parse_extended(&bytes, false).unwrap().unwrap();
+        assert_eq!(parsed, packet);
+        assert_eq!(consumed, bytes.len());
+        assert_eq!(Packet::parse_extended(&bytes[..100], false).unwrap(), None);
+        assert_eq!(Packet::from_bytes(&bytes), Ok(packet));
+    }
+
+    #[test]
+    fn test_extended_marker_without_extended_length() {
+        // A zero length byte is noise unless extended lengths are enabled
+        assert_eq!(Packet::parse(&[0xFE, 0xFE, 0x00, 0x01]), Err(FrameError::InvalidLength));
+        assert_eq!(Packet::parse_extended(&[0xFE, 0xFE, 0x00, 0x01], false), Ok(None));
+    }
+
+    #[test]
+    fn test_extended_length_short_payload() {
+        // Short payloads keep the one-byte length even with the flag set
+        let mut packet = Packet::new(Command::PowerOn, vec![]);
+        packet.extended_length = true;
+        assert_eq!(packet.to_bytes(), vec![0xFE, 0xFE, 0x02, 0x10, 0xFA]);
+    }
+
+    #[test]
+    fn test_long_payload_without_extended_length() {
+        let packet = Packet::new(Command::GetEncoders, vec![0; 300]);
+        assert_eq!(packet.try_to_bytes(), Err(FrameError::PayloadTooLong(300)));
+    }
 }
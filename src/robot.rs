@@ -15,10 +15,16 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use crate::commands::Command;
+use crate::clock::{Clock, SystemClock};
+use crate::commands::{Command, GripperType, MoveMode, PinMode};
 use crate::io::SerialPort;
-use crate::protocol::Packet;
-use std::time::Duration;
+use crate::jog::JogController;
+use crate::model::RobotModel;
+use crate::protocol::{decode_i16_be, encode_i16_be, Direction, FrameError, Packet, HEADER};
+use crate::types::{hsv_to_rgb, AngleUnit, Axis, ClampMode, Color, Coords, JointAngles, ServoStatus};
+use std::collections::VecDeque;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
 use log::{debug, warn};
 use thiserror::Error;
 
@@ -26,225 +32,1987 @@ use thiserror::Error;
 pub enum Error {
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
-    #[error("Protocol error: {0}")]
-    Protocol(String),
+    /// A frame answered a different command than the one expected.
+    #[error("Expected a response to {expected:?}, got {got:?}")]
+    UnexpectedCommand { expected: Command, got: Command },
+    /// A response payload had the wrong number of bytes.
+    #[error("Expected {expected} bytes, got {got}")]
+    LengthMismatch { expected: usize, got: usize },
+    /// A frame's checksum byte didn't match its contents.
+    #[error("Checksum mismatch")]
+    ChecksumMismatch,
     #[error("Timeout waiting for response")]
     Timeout,
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
+    #[error("Controller error: {0:?}")]
+    Controller(RobotError),
+    /// The overload guard released all servos after the controller reported
+    /// an overload, see `MyCobot::set_overload_guard`.
+    #[error("Servos released after overload: {0:?}")]
+    OverloadProtection(RobotError),
+    /// A motion ended before reaching its target, e.g. after `stop` or `pause`.
+    #[error("Motion stopped before reaching the target")]
+    MotionStopped,
+}
+
+impl Error {
+    /// Whether the same call may succeed when retried, e.g. after a garbled
+    /// or lost frame. Invalid parameters and controller errors are fatal.
+    pub fn is_recoverable(&self) -> bool {
+        match self {
+            Error::Io(e) => matches!(
+                e.kind(),
+                std::io::ErrorKind::TimedOut | std::io::ErrorKind::Interrupted | std::io::ErrorKind::WouldBlock
+            ),
+            Error::Timeout
+            | Error::UnexpectedCommand { .. }
+            | Error::LengthMismatch { .. }
+            | Error::ChecksumMismatch => true,
+            Error::InvalidParameter(_)
+            | Error::Controller(_)
+            | Error::OverloadProtection(_)
+            | Error::MotionStopped => false,
+        }
+    }
 }
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+/// An error reported by the controller through its error queue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RobotError {
+    /// Joint (1-6) exceeded its limit.
+    JointLimit(u8),
+    /// Collision protection triggered (raw code 16-19).
+    Collision(u8),
+    /// No inverse kinematics solution for the requested pose.
+    NoSolution,
+    /// No adjacent solution during linear motion (raw code 33-34).
+    NoAdjacentSolution(u8),
+    Unknown(u8),
+}
+
+impl RobotError {
+    /// Decodes a raw error code. Returns `None` for 0 (no error).
+    pub fn from_code(code: u8) -> Option<Self> {
+        match code {
+            0 => None,
+            1..=6 => Some(RobotError::JointLimit(code)),
+            16..=19 => Some(RobotError::Collision(code)),
+            32 => Some(RobotError::NoSolution),
+            33..=34 => Some(RobotError::NoAdjacentSolution(code)),
+            c => Some(RobotError::Unknown(c)),
+        }
+    }
+
+    /// Whether the error means a servo is overloaded. Collision protection is
+    /// triggered by the servo current exceeding its threshold, so collisions
+    /// count as overloads.
+    pub fn is_overload(&self) -> bool {
+        matches!(self, RobotError::Collision(_))
+    }
+}
+
+/// GPIO pins on the Atom's header, in the bit order used by `get_digital_inputs`.
+pub const ATOM_GPIO_PINS: [u8; 4] = [19, 22, 23, 33];
+
+/// Returns the state of `pin` in a mask from `get_digital_inputs`,
+/// or `None` if the pin is not one of `ATOM_GPIO_PINS`.
+pub fn pin_state(mask: u16, pin: u8) -> Option<bool> {
+    let bit = ATOM_GPIO_PINS.iter().position(|&p| p == pin)?;
+    Some(mask & (1 << bit) != 0)
+}
+
+/// Snapshot of the controller state returned by `MyCobot::get_status`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RobotStatus {
+    pub powered: bool,
+    pub moving: bool,
+    pub paused: bool,
+    /// The oldest error in the controller's queue, which reading removes.
+    pub pending_error: Option<RobotError>,
+}
+
+/// Default time to wait for a response to a request.
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Maximum number of unsolicited packets kept for `poll_async`.
+/// The oldest packets are dropped once the queue is full.
+pub const FEEDBACK_QUEUE_LEN: usize = 64;
+
+/// Default for how long a power state read by `is_powered_on` is trusted,
+/// see `set_power_state_ttl`.
+pub const POWER_STATE_TTL: Duration = Duration::from_secs(1);
+
+/// How often `power_on_blocking` checks whether the servos are powered.
+pub const POWER_ON_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// How often blocking moves like `play_trajectory` check whether the target has been reached.
+pub const MOVE_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Longest time blocking moves like `play_trajectory` wait for a single target.
+pub const MOVE_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Most waypoints `move_linear` sends for a single move.
+pub const MAX_LINEAR_STEPS: usize = 10_000;
+
+/// Maximum number of bytes taken from the port per read.
+pub(crate) const READ_CHUNK_LEN: usize = 64;
+
+/// Encoders are 14-bit.
+pub const MAX_ENCODER: u16 = 16383;
+
+/// (offset, degrees per count) of the MyCobot 280 servos: 2048 counts at
+/// zero and 4096 counts per turn.
+pub const DEFAULT_ENCODER_CALIBRATION: (f32, f32) = (2048.0, 360.0 / 4096.0);
+
+/// How often and how patiently to re-open the port after an IO error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// Number of reconnect attempts before the error is returned.
+    pub max_retries: u32,
+    /// Delay before the first attempt, doubled on every further attempt.
+    pub backoff: Duration,
+}
+
+impl RetryPolicy {
+    pub fn new(max_retries: u32, backoff: Duration) -> Self {
+        Self { max_retries, backoff }
+    }
+
+    fn delay(&self, attempt: u32) -> Duration {
+        self.backoff.saturating_mul(1 << attempt.min(16))
+    }
+}
+
+/// Callback for raw frames, see `MyCobot::set_frame_observer`. `Sync` keeps
+/// `MyCobot` shareable behind an `Arc`.
+pub type FrameObserver = Box<dyn FnMut(Direction, &[u8]) + Send + Sync>;
+
 pub struct MyCobot<P: SerialPort> {
     pub port: P,
     debug_mode: bool,
+    /// Timeout used by all requests that don't specify their own.
+    timeout: Duration,
+    /// Path and baud rate the port was opened with, needed to reconnect.
+    device: Option<(PathBuf, u32)>,
+    reconnect_policy: Option<RetryPolicy>,
+    /// How often a request is re-sent after a response of the wrong length.
+    read_retries: u32,
+    /// Minimum spacing between consecutive frames.
+    min_command_interval: Duration,
+    /// When the last frame was written, for pacing.
+    last_write: Option<Instant>,
+    /// Whether long payloads may be sent with the extended length field.
+    extended_length: bool,
+    /// Whether frames carry a checksum byte before the footer.
+    checksum: bool,
+    /// Whether stale input is discarded before each request.
+    flush_before_request: bool,
+    /// Whether motion commands are refused while the robot is powered off.
+    require_power: bool,
+    /// Whether `set_servo_calibration` may be sent.
+    allow_calibration: bool,
+    /// Whether an overload reported by `read_next_error` releases all servos.
+    overload_guard: bool,
+    /// Last known power state and when it was read.
+    power_state: Option<(Instant, bool)>,
+    /// How long `power_state` is trusted.
+    power_state_ttl: Duration,
+    model: RobotModel,
+    /// Unit of angles and rotations passed to and returned from the API.
+    angle_unit: AngleUnit,
+    /// Gripper type appended to gripper commands, if set.
+    gripper_type: Option<GripperType>,
+    /// What happens to targets outside `angle_limits` and `coord_limits`.
+    clamp_mode: ClampMode,
+    /// Upper bound for the speed of every motion command.
+    max_speed: u8,
+    /// Upper bound for the speed of moves involving each joint.
+    joint_max_speeds: [u8; 6],
+    /// (min, max) per joint, checked before any angle is sent.
+    angle_limits: [(f32, f32); 6],
+    /// (min, max) per axis, checked before any coordinate is sent.
+    coord_limits: [(f32, f32); 6],
+    /// (offset, degrees per count) per joint for `get_angles_from_encoders`.
+    encoder_calibration: [(f32, f32); 6],
+    /// Scratch buffer the next frame is encoded into.
+    tx_buffer: Vec<u8>,
+    /// Frames from `queue_command` not written yet.
+    queued: Vec<u8>,
+    /// Command and length of each frame in `queued`, reported when written.
+    queued_frames: Vec<(Command, usize)>,
+    /// Received bytes not yet parsed into a packet.
+    rx_buffer: Vec<u8>,
+    /// Unsolicited packets waiting for `poll_async`, oldest first.
+    feedback: VecDeque<Packet>,
+    /// Receives every raw frame instead of the debug log.
+    frame_observer: Option<FrameObserver>,
+    /// The most recent response matched to a request.
+    last_response: Option<Packet>,
+    /// Time source for timeouts, polling and pacing.
+    clock: Box<dyn Clock>,
+}
+
+impl MyCobot<serial2::SerialPort> {
+    /// Opens the serial port at `path`, same as [`MyCobot::open`]. Reads wait
+    /// for the robot's timeout, see `set_timeout`.
+    ///
+    /// ```no_run
+    /// let mut robot = mycobot_rs::MyCobot::new_from_path("/dev/ttyAMA0", 1_000_000)?;
+    /// robot.power_on()?;
+    /// # Ok::<(), mycobot_rs::Error>(())
+    /// ```
+    pub fn new_from_path(path: &str, baud_rate: u32) -> Result<Self> {
+        Self::open(path, baud_rate)
+    }
 }
 
 impl<P: SerialPort> MyCobot<P> {
+    /// Wraps `port` talking to a MyCobot 280.
     pub fn new(port: P) -> Self {
+        Self::with_model(port, RobotModel::MyCobot280)
+    }
+
+    pub fn with_model(port: P, model: RobotModel) -> Self {
         Self {
             port,
             debug_mode: false,
+            timeout: DEFAULT_TIMEOUT,
+            device: None,
+            reconnect_policy: None,
+            read_retries: 0,
+            min_command_interval: Duration::ZERO,
+            last_write: None,
+            extended_length: false,
+            checksum: false,
+            flush_before_request: false,
+            require_power: false,
+            allow_calibration: false,
+            overload_guard: false,
+            power_state: None,
+            power_state_ttl: POWER_STATE_TTL,
+            model,
+            angle_unit: AngleUnit::Degrees,
+            gripper_type: None,
+            clamp_mode: ClampMode::Reject,
+            max_speed: 100,
+            joint_max_speeds: [100; 6],
+            angle_limits: model.angle_limits(),
+            coord_limits: model.coord_limits(),
+            encoder_calibration: [DEFAULT_ENCODER_CALIBRATION; 6],
+            tx_buffer: Vec::new(),
+            queued: Vec::new(),
+            queued_frames: Vec::new(),
+            rx_buffer: Vec::new(),
+            feedback: VecDeque::new(),
+            frame_observer: None,
+            last_response: None,
+            clock: Box::new(SystemClock),
         }
     }
 
+    /// Opens the device at `path` and remembers it so the port can be
+    /// re-opened by a reconnect policy.
+    pub fn open(path: impl AsRef<Path>, baud_rate: u32) -> Result<Self> {
+        let path = path.as_ref();
+        let mut robot = Self::new(P::open_device(path, baud_rate)?);
+        robot.device = Some((path.to_path_buf(), baud_rate));
+        Ok(robot)
+    }
+
+    /// Consumes the robot and returns the wrapped port.
+    pub fn into_inner(self) -> P {
+        self.port
+    }
+
+    /// Mutable access to the wrapped port, e.g. to inspect a `MockSerial` between calls.
+    pub fn port_mut(&mut self) -> &mut P {
+        &mut self.port
+    }
+
+    /// Returns whether debug logging of raw frames is enabled.
+    pub fn debug_mode(&self) -> bool {
+        self.debug_mode
+    }
+
     pub fn set_debug_mode(&mut self, debug: bool) {
         self.debug_mode = debug;
     }
 
+    /// Calls `observer` with every frame written to or parsed from the port,
+    /// e.g. for a protocol analyzer. Replaces the debug log of raw frames.
+    pub fn set_frame_observer(&mut self, observer: FrameObserver) {
+        self.frame_observer = Some(observer);
+    }
+
+    /// Replaces the system clock, e.g. with a `FakeClock` to test timeouts
+    /// without waiting for them.
+    pub fn set_clock(&mut self, clock: impl Clock + 'static) {
+        self.clock = Box::new(clock);
+    }
+
+    /// Sets the default response timeout used by all requests.
+    pub fn set_timeout(&mut self, timeout: Duration) {
+        self.timeout = timeout;
+    }
+
+    /// Returns the default response timeout.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+
+    pub fn model(&self) -> RobotModel {
+        self.model
+    }
+
+    /// Switches to another model and resets the angle and coordinate limits to its defaults.
+    pub fn set_model(&mut self, model: RobotModel) {
+        self.model = model;
+        self.angle_limits = model.angle_limits();
+        self.coord_limits = model.coord_limits();
+    }
+
+    /// Caps the speed of all motion commands. Higher speeds passed to e.g.
+    /// `write_angles` are clamped to this instead of being rejected.
+    pub fn set_max_speed(&mut self, speed: u8) -> Result<()> {
+        check_speed(speed)?;
+        self.max_speed = speed;
+        Ok(())
+    }
+
+    /// Caps the speed of moves involving `joint` (1-6).
+    ///
+    /// The firmware only takes one speed per move, so this is applied in software:
+    /// `write_angle` and `jog_angle` are clamped to the cap of their joint,
+    /// `write_angles` to the lowest cap of all joints.
+    pub fn set_joint_max_speed(&mut self, joint: u8, speed: u8) -> Result<()> {
+        check_index("joint", joint, self.model.dof())?;
+        check_speed(speed)?;
+        self.joint_max_speeds[joint as usize - 1] = speed;
+        Ok(())
+    }
+
+    /// Clamps `speed` to the global cap and, if given, the cap of a 1-based joint.
+    fn capped_speed(&self, speed: u8, joint: Option<u8>) -> u8 {
+        let speed = speed.min(self.max_speed);
+        match joint {
+            Some(joint) => speed.min(self.joint_max_speeds[joint as usize - 1]),
+            None => speed,
+        }
+    }
+
+    pub fn angle_unit(&self) -> AngleUnit {
+        self.angle_unit
+    }
+
+    /// Makes joint angles and the rotations of coordinates be passed and
+    /// returned in `unit`. Limits stay in degrees. Defaults to degrees.
+    pub fn set_angle_unit(&mut self, unit: AngleUnit) {
+        self.angle_unit = unit;
+    }
+
+    pub fn clamp_mode(&self) -> ClampMode {
+        self.clamp_mode
+    }
+
+    /// Makes motion commands clamp angles and coordinates to the limits
+    /// instead of rejecting them, e.g. for teleoperation where small overshoots
+    /// shouldn't abort the session. Non-finite values are always rejected.
+    pub fn set_clamp_mode(&mut self, mode: ClampMode) {
+        self.clamp_mode = mode;
+    }
+
+    /// Overrides the (min, max) angle of each joint in degrees.
+    /// Defaults to the limits of the model.
+    pub fn set_angle_limits(&mut self, limits: [(f32, f32); 6]) {
+        self.angle_limits = limits;
+    }
+
+    pub fn angle_limits(&self) -> [(f32, f32); 6] {
+        self.angle_limits
+    }
+
+    /// Overrides the (min, max) of each Cartesian axis, millimeters and degrees.
+    /// Defaults to the limits of the model.
+    pub fn set_coord_limits(&mut self, limits: [(f32, f32); 6]) {
+        self.coord_limits = limits;
+    }
+
+    pub fn coord_limits(&self) -> [(f32, f32); 6] {
+        self.coord_limits
+    }
+
+    /// Re-opens the port after IO errors according to `policy`.
+    /// Only has an effect on robots created with `open`, since the device path is needed.
+    pub fn set_reconnect_policy(&mut self, policy: RetryPolicy) {
+        self.reconnect_policy = Some(policy);
+    }
+
+    /// Spaces consecutive frames by at least `interval`, sleeping before a write
+    /// if needed. The controller drops commands that arrive too quickly. Defaults to zero.
+    pub fn set_min_command_interval(&mut self, interval: Duration) {
+        self.min_command_interval = interval;
+    }
+
+    /// Allows requests with payloads over 253 bytes, framed with the extended
+    /// length field of newer controllers. Received frames are only parsed in the
+    /// extended form while this is enabled, a zero length byte is noise otherwise.
+    pub fn set_extended_length(&mut self, enabled: bool) {
+        self.extended_length = enabled;
+    }
+
+    /// Frames requests with a checksum byte before the footer and verifies it
+    /// on received frames, for firmware that uses one. A request answered with
+    /// a corrupted frame fails with `Error::ChecksumMismatch`. Off by default,
+    /// as the stock firmware has no checksum.
+    pub fn set_checksum(&mut self, enabled: bool) {
+        self.checksum = enabled;
+    }
+
+    /// Discards stale input with `flush_input` before every request, e.g. the late
+    /// response to a request that timed out. Unsolicited packets that arrive
+    /// between requests are lost instead of being kept for `poll_async`.
+    pub fn set_flush_before_request(&mut self, enabled: bool) {
+        self.flush_before_request = enabled;
+    }
+
+    /// Discards all bytes received but not parsed yet, both buffered here and
+    /// pending on the port.
+    pub fn flush_input(&mut self) -> Result<()> {
+        self.rx_buffer.clear();
+        self.port.discard_input()?;
+        Ok(())
+    }
+
+    /// Makes motion commands check that the robot is powered on first and fail
+    /// with `Error::InvalidParameter` otherwise, instead of being ignored by the
+    /// controller. Uses the cached power state, see `set_power_state_ttl`.
+    pub fn set_require_power(&mut self, require: bool) {
+        self.require_power = require;
+    }
+
+    /// Allows `set_servo_calibration`, which is refused with
+    /// `Error::InvalidParameter` by default to avoid zeroing a joint by accident.
+    pub fn set_allow_calibration(&mut self, allow: bool) {
+        self.allow_calibration = allow;
+    }
+
+    /// Makes `read_next_error`, and with it `get_status`, release all servos and
+    /// fail with `Error::OverloadProtection` when the controller reports an
+    /// overload, see `RobotError::is_overload`. Off by default.
+    pub fn set_overload_guard(&mut self, enabled: bool) {
+        self.overload_guard = enabled;
+    }
+
+    /// Copies one value per joint or axis of the model into an array, zeroing
+    /// the slots the model doesn't have.
+    fn padded(&self, name: &str, values: &[f32]) -> Result<[f32; 6]> {
+        let dof = self.model.dof();
+        if values.len() != dof {
+            return Err(Error::InvalidParameter(format!(
+                "expected {} {} values, got {}",
+                dof,
+                name,
+                values.len()
+            )));
+        }
+        let mut array = [0.0; 6];
+        array[..dof].copy_from_slice(values);
+        Ok(array)
+    }
+
+    fn check_powered(&mut self) -> Result<()> {
+        if !self.require_power {
+            return Ok(());
+        }
+        if self.is_powered_on_cached()? {
+            Ok(())
+        } else {
+            Err(Error::InvalidParameter("not powered on".into()))
+        }
+    }
+
+    /// Re-sends a request up to `retries` times if the response has the wrong length,
+    /// e.g. on a noisy link. Defaults to 0.
+    pub fn set_read_retries(&mut self, retries: u32) {
+        self.read_retries = retries;
+    }
+
+    /// Runs `op`, re-opening the port and retrying on IO errors if a reconnect policy is set.
+    fn with_reconnect<T>(&mut self, mut op: impl FnMut(&mut Self) -> Result<T>) -> Result<T> {
+        let mut attempt = 0;
+        loop {
+            let err = match op(self) {
+                Err(Error::Io(e)) => e,
+                result => return result,
+            };
+            let (Some(policy), Some((path, baud_rate))) = (self.reconnect_policy, self.device.clone()) else {
+                return Err(Error::Io(err));
+            };
+            if attempt >= policy.max_retries {
+                return Err(Error::Io(err));
+            }
+
+            self.clock.sleep(policy.delay(attempt));
+            attempt += 1;
+            warn!("IO error: {}, reconnecting to {} ({}/{})", err, path.display(), attempt, policy.max_retries);
+            match P::open_device(&path, baud_rate) {
+                Ok(port) => {
+                    self.port = port;
+                    self.rx_buffer.clear();
+                }
+                Err(e) => warn!("Reconnect failed: {}", e),
+            }
+        }
+    }
+
     /// Helper to write a command without waiting for response
     fn write_command(&mut self, command: Command, payload: Vec<u8>) -> Result<()> {
-        let packet = Packet::new(command, payload);
-        let bytes = packet.to_bytes();
-        if self.debug_mode {
-            debug!("Writing: {:02X?}", bytes);
+        #[cfg(feature = "tracing")]
+        let _span =
+            tracing::debug_span!("mycobot.write_command", command = ?command, payload_len = payload.len()).entered();
+        self.encode_frame(command, payload)?;
+        self.with_reconnect(|robot| robot.write_frame(command))
+    }
+
+    /// Writes the frame in `tx_buffer`, encoded once so that retries resend it as is.
+    fn write_frame(&mut self, command: Command) -> Result<()> {
+        // Queued frames go first to keep the order
+        self.flush_commands()?;
+        report_frame(&mut self.frame_observer, self.debug_mode, command, &self.tx_buffer);
+        self.pace();
+        self.port.write_all(&self.tx_buffer)?;
+        std::io::Write::flush(&mut self.port)?;
+        self.last_write = Some(self.clock.now());
+        Ok(())
+    }
+
+    /// Encodes a frame into `tx_buffer`.
+    fn encode_frame(&mut self, command: Command, payload: Vec<u8>) -> Result<()> {
+        let mut packet = Packet::new(command, payload);
+        packet.extended_length = self.extended_length;
+        packet.checksum = self.checksum;
+        packet
+            .encode_into(&mut self.tx_buffer)
+            .map_err(|e| Error::InvalidParameter(format!("{:?}: {}", command, e)))
+    }
+
+    /// Waits until `min_command_interval` has passed since the last write.
+    fn pace(&mut self) {
+        if let Some(last) = self.last_write {
+            let wait = self.min_command_interval.saturating_sub(self.clock.now() - last);
+            if !wait.is_zero() {
+                self.clock.sleep(wait);
+            }
+        }
+    }
+
+    /// Buffers a fire-and-forget command instead of writing it, so a batch of
+    /// commands costs a single write with `flush_commands`. Any other write or
+    /// request flushes the queue first, keeping the order. Queued frames are
+    /// only reported to the frame observer once they are written.
+    pub fn queue_command(&mut self, command: Command, payload: Vec<u8>) -> Result<()> {
+        self.encode_frame(command, payload)?;
+        self.queued.extend_from_slice(&self.tx_buffer);
+        self.queued_frames.push((command, self.tx_buffer.len()));
+        Ok(())
+    }
+
+    /// Like `write_angles`, but queued, see `queue_command`.
+    ///
+    /// With `set_require_power`, checking the power state may send a request,
+    /// which writes the frames queued so far first. The batch then goes out in
+    /// two writes instead of one.
+    pub fn queue_angles(&mut self, angles: impl Into<[f32; 6]>, speed: u8) -> Result<()> {
+        let payload = self.angles_payload(angles.into(), speed)?;
+        self.queue_command(Command::WriteAngles, payload)
+    }
+
+    /// Like `write_coords`, but queued, see `queue_command` and `queue_angles`.
+    pub fn queue_coords(&mut self, coords: impl Into<[f32; 6]>, speed: u8, mode: MoveMode) -> Result<()> {
+        let payload = self.coords_payload(coords.into(), speed, mode)?;
+        self.queue_command(Command::WriteCoords, payload)
+    }
+
+    /// Writes all queued commands at once. The pacing from
+    /// `set_min_command_interval` only applies before the batch. If the write
+    /// fails, the commands stay queued for the next flush.
+    pub fn flush_commands(&mut self) -> Result<()> {
+        if self.queued.is_empty() {
+            return Ok(());
+        }
+        let mut start = 0;
+        for &(command, len) in &self.queued_frames {
+            report_frame(&mut self.frame_observer, self.debug_mode, command, &self.queued[start..start + len]);
+            start += len;
         }
-        self.port.write_all(&bytes)?;
+        self.pace();
+        self.port.write_all(&self.queued)?;
         std::io::Write::flush(&mut self.port)?;
+        self.queued.clear();
+        self.queued_frames.clear();
+        self.last_write = Some(self.clock.now());
         Ok(())
     }
 
     /// Helper to write a command and wait for a response
     /// Returns the payload of the response packet
     fn request(&mut self, command: Command, payload: Vec<u8>, timeout: Duration) -> Result<Vec<u8>> {
-        self.write_command(command, payload)?;
-
-        // Simple blocking read loop with timeout
-        // Since we are using serial2 in blocking mode or with timeouts set on the port,
-        // we can try to read byte by byte or in chunks.
-        // For simplicity in this initial blocking version, we'll read byte-by-byte to parse.
-        // A better approach for serial2 is to set a read timeout on the port itself.
-        
-        let start = std::time::Instant::now();
-        let mut buffer = Vec::new();
-        let mut temp_buf = [0u8; 1];
+        #[cfg(feature = "tracing")]
+        let span = tracing::debug_span!(
+            "mycobot.request",
+            command = ?command,
+            payload_len = payload.len(),
+            elapsed = tracing::field::Empty
+        )
+        .entered();
+        #[cfg(feature = "tracing")]
+        let start = self.clock.now();
+
+        let mut attempt = 0;
+        let result = self.encode_frame(command, payload).and_then(|()| loop {
+            match self.with_reconnect(|robot| robot.request_once(command, timeout)) {
+                Err(e @ Error::LengthMismatch { .. }) if attempt < self.read_retries => {
+                    attempt += 1;
+                    warn!("{} for {:?}, retrying ({}/{})", e, command, attempt, self.read_retries);
+                }
+                result => break result,
+            }
+        });
+
+        #[cfg(feature = "tracing")]
+        span.record("elapsed", tracing::field::debug(self.clock.now() - start));
+        result
+    }
+
+    fn request_once(&mut self, command: Command, timeout: Duration) -> Result<Vec<u8>> {
+        if self.flush_before_request {
+            self.flush_input()?;
+        }
+        self.write_frame(command)?;
+
+        // Read whatever is available until a matching packet is parsed. Bytes after
+        // it stay in the receive buffer for the next request or `poll_async`.
+        // The port is responsible for blocking until data arrives or the remaining time runs out.
+        let start = self.clock.now();
+        let mut temp_buf = [0u8; READ_CHUNK_LEN];
 
         loop {
-            if start.elapsed() > timeout {
-                return Err(Error::Timeout);
+            while let Some(packet) = self.next_packet()? {
+                // Getters are answered with the same command byte
+                match parse_response(self.model, command, &packet) {
+                    Ok(()) => {
+                        let payload = packet.payload.clone();
+                        self.last_response = Some(packet);
+                        return Ok(payload);
+                    }
+                    Err(e @ Error::UnexpectedCommand { .. }) => {
+                        // Might be old data or an unsolicited message.
+                        // Keep it for `poll_async` and keep waiting for our response.
+                        warn!("{}", e);
+                        self.queue_feedback(packet);
+                    }
+                    Err(e) => return Err(e),
+                }
             }
 
-            // This read might block depending on port config. 
-            // We assume the user has configured the port with a timeout or is using non-blocking with retry.
-            // But here we are wrapping a generic SerialPort trait which is just Read+Write.
-            // We should use a loop with short sleeps if the read returns 0/WouldBlock, but std::io::Read 
-            // doesn't guarantee timeout behavior without trait support.
-            
-            // For the purpose of this library, we assume the underlying port handles blocking/timeout
-            // or returns quickly.
-            
-            match self.port.read(&mut temp_buf) {
+            let remaining = timeout.checked_sub(self.clock.now() - start).ok_or(Error::Timeout)?;
+
+            match self.port.read_with_timeout(&mut temp_buf, remaining) {
                 Ok(0) => {
-                    // EOF or no data yet?
-                    std::thread::sleep(Duration::from_millis(1));
-                    continue;
+                    // EOF or no data yet, the deadline check above ends the wait
+                    self.clock.sleep(Duration::from_millis(1));
+                }
+                Ok(n) => self.rx_buffer.extend_from_slice(&temp_buf[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::TimedOut => return Err(Error::Timeout),
+                Err(e) => return Err(Error::Io(e)),
+            }
+        }
+    }
+
+    /// Parses the next complete packet from the receive buffer, skipping invalid data.
+    /// Partial frames stay buffered for the next read. A frame with a bad
+    /// checksum is dropped and reported as `Error::ChecksumMismatch`.
+    fn next_packet(&mut self) -> Result<Option<Packet>> {
+        loop {
+            // Drop anything before the next header in one go
+            match Packet::find_header(&self.rx_buffer) {
+                Some(start) => {
+                    self.rx_buffer.drain(..start);
                 }
-                Ok(1) => {
-                    buffer.push(temp_buf[0]);
-                    // Try to parse
-                    match Packet::parse(&buffer) {
-                        Ok(Some((packet, _consumed))) => {
-                             if self.debug_mode {
-                                debug!("Received: {:?} {:02X?}", packet.command, packet.payload);
-                            }
-                            // Does the response command match? 
-                            // Usually response command is same as request for getters.
-                            if packet.command == command {
-                                return Ok(packet.payload);
-                            } else {
-                                // Mismatch, might be old data or async message. 
-                                // For now, log and continue or return error?
-                                // Let's simplify: return it if it's not a known async packet.
-                                warn!("Received unexpected command {:?} waiting for {:?}", packet.command, command);
-                                // Reset buffer to search for next packet? 
-                                // Packet::parse consumes bytes conceptually but here we just have the full buffer.
-                                // If we found a packet but it's wrong, we should ideally consume it and continue.
-                                // But Packet::parse returns (packet, bytes_consumed).
-                                // We need to remove the consumed bytes.
-                            }
-                        },
-                        Ok(None) => continue, // Need more data
-                        Err(_e) => {
-                             // Invalid data, maybe skip one byte?
-                             if buffer.len() > 0 {
-                                 buffer.remove(0);
-                             }
-                             continue;
-                        }
+                None => {
+                    // Keep a trailing sync byte, it may be the start of a header
+                    let keep = usize::from(self.rx_buffer.last() == Some(&HEADER[0]));
+                    let len = self.rx_buffer.len();
+                    self.rx_buffer.drain(..len - keep);
+                    return Ok(None);
+                }
+            }
+
+            let parsed = if self.extended_length {
+                Packet::parse_extended(&self.rx_buffer, self.checksum)
+            } else if self.checksum {
+                Packet::parse_with_checksum(&self.rx_buffer)
+            } else {
+                Packet::parse(&self.rx_buffer)
+            };
+            match parsed {
+                Ok(Some((packet, consumed))) => {
+                    #[cfg(feature = "tracing")]
+                    tracing::trace!(
+                        direction = "rx",
+                        command = ?packet.command,
+                        frame = ?&self.rx_buffer[..consumed],
+                        "frame"
+                    );
+                    if let Some(observer) = &mut self.frame_observer {
+                        observer(Direction::Rx, &self.rx_buffer[..consumed]);
+                    } else if self.debug_mode {
+                        debug!("Received: {}", packet.describe());
                     }
+                    self.rx_buffer.drain(..consumed);
+                    return Ok(Some(packet));
+                }
+                Ok(None) => return Ok(None), // Need more data
+                Err(FrameError::ChecksumMismatch) => {
+                    self.rx_buffer.drain(..HEADER.len());
+                    return Err(Error::ChecksumMismatch);
                 }
-                Ok(_) => unreachable!(), // we asked for 1 byte
-                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => {
-                     std::thread::sleep(Duration::from_millis(1));
-                     continue;
+                Err(_) => {
+                    // Header without a valid frame, resync after it
+                    self.rx_buffer.drain(..1);
                 }
+            }
+        }
+    }
+
+    fn queue_feedback(&mut self, packet: Packet) {
+        if self.feedback.len() == FEEDBACK_QUEUE_LEN {
+            self.feedback.pop_front();
+        }
+        self.feedback.push_back(packet);
+    }
+
+    /// Sends a raw command without waiting for a response.
+    /// Escape hatch for firmware commands not wrapped by this crate, see `Command::Unknown`.
+    pub fn send(&mut self, command: Command, payload: Vec<u8>) -> Result<()> {
+        self.write_command(command, payload)
+    }
+
+    /// Sends a raw command and returns the payload of the response with the same command byte.
+    /// Use `Command::expects_response` to decide between this and `send`.
+    pub fn send_recv(&mut self, command: Command, payload: Vec<u8>, timeout: Duration) -> Result<Vec<u8>> {
+        self.request(command, payload, timeout)
+    }
+
+    /// The last response packet returned by a request, for inspecting the raw
+    /// payload behind a decoded value.
+    pub fn last_response(&self) -> Option<&Packet> {
+        self.last_response.as_ref()
+    }
+
+    /// Returns all packets received without a matching request: whatever is
+    /// buffered on the port right now plus anything that arrived during earlier
+    /// requests. Does not send anything and does not block.
+    pub fn poll_async(&mut self) -> Result<Vec<Packet>> {
+        let mut chunk = [0u8; READ_CHUNK_LEN];
+        loop {
+            match self.port.read_with_timeout(&mut chunk, Duration::ZERO) {
+                Ok(0) => break,
+                Ok(n) => self.rx_buffer.extend_from_slice(&chunk[..n]),
+                Err(e) if matches!(e.kind(), std::io::ErrorKind::TimedOut | std::io::ErrorKind::WouldBlock) => break,
                 Err(e) => return Err(Error::Io(e)),
             }
         }
+        while let Some(packet) = self.next_packet()? {
+            self.queue_feedback(packet);
+        }
+        Ok(self.feedback.drain(..).collect())
+    }
+
+    /// Helper for requests answered with a single boolean byte
+    fn request_bool(&mut self, command: Command, payload: Vec<u8>, timeout: Duration) -> Result<bool> {
+        Ok(self.request_u8(command, payload, timeout)? == 1)
+    }
+
+    /// Helper for requests answered with a single byte
+    fn request_u8(&mut self, command: Command, payload: Vec<u8>, timeout: Duration) -> Result<u8> {
+        let response = self.request(command, payload, timeout)?;
+        response
+            .first()
+            .copied()
+            .ok_or(Error::LengthMismatch { expected: 1, got: 0 })
     }
 
     // --- Basic Control ---
 
+    /// Reads the firmware version, e.g. `2.1`.
+    /// The controller reports it as a single byte scaled by 10.
+    pub fn get_version(&mut self) -> Result<f32> {
+        let version = self.request_u8(Command::Version, vec![], self.timeout)?;
+        Ok(version as f32 / 10.0)
+    }
+
     pub fn power_on(&mut self) -> Result<()> {
+        // The servos take a moment to power up, so ask again next time
+        self.power_state = None;
         self.write_command(Command::PowerOn, vec![])
     }
 
+    /// Powers on and waits until the controller reports the servos as powered,
+    /// polling every `POWER_ON_POLL_INTERVAL`. Fails with `Error::Timeout` after `timeout`.
+    pub fn power_on_blocking(&mut self, timeout: Duration) -> Result<()> {
+        self.power_on()?;
+        let start = self.clock.now();
+        while !self.is_powered_on()? {
+            if self.clock.now() - start > timeout {
+                return Err(Error::Timeout);
+            }
+            self.clock.sleep(POWER_ON_POLL_INTERVAL);
+        }
+        Ok(())
+    }
+
     pub fn power_off(&mut self) -> Result<()> {
-        self.write_command(Command::PowerOff, vec![])
+        // Only known to be off once the command went out
+        self.power_state = None;
+        self.write_command(Command::PowerOff, vec![])?;
+        self.power_state = Some((self.clock.now(), false));
+        Ok(())
     }
     
     pub fn is_powered_on(&mut self) -> Result<bool> {
-        let response = self.request(Command::IsPoweredOn, vec![], Duration::from_millis(500))?;
-        if response.len() == 1 {
-            Ok(response[0] == 1)
-        } else {
-            Err(Error::Protocol("Invalid payload length for IsPoweredOn".into()))
+        self.is_powered_on_with_timeout(self.timeout)
+    }
+
+    pub fn is_powered_on_with_timeout(&mut self, timeout: Duration) -> Result<bool> {
+        let powered = self.request_bool(Command::IsPoweredOn, vec![], timeout)?;
+        self.power_state = Some((self.clock.now(), powered));
+        Ok(powered)
+    }
+
+    /// Sets how long a power state read by `is_powered_on` is trusted by
+    /// `cached_power_state` and `is_powered_on_cached`. Zero disables the cache.
+    /// Defaults to `POWER_STATE_TTL`.
+    pub fn set_power_state_ttl(&mut self, ttl: Duration) {
+        self.power_state_ttl = ttl;
+    }
+
+    /// The power state last read by `is_powered_on` or set by `power_off`, if
+    /// it is younger than the TTL. `power_on` clears it, as the servos take a
+    /// moment to power up.
+    pub fn cached_power_state(&self) -> Option<bool> {
+        match self.power_state {
+            Some((checked, powered)) if self.clock.now() - checked < self.power_state_ttl => Some(powered),
+            _ => None,
+        }
+    }
+
+    /// Like `is_powered_on`, but answers from the cache while it is fresh,
+    /// e.g. for polling in a UI loop.
+    pub fn is_powered_on_cached(&mut self) -> Result<bool> {
+        match self.cached_power_state() {
+            Some(powered) => Ok(powered),
+            None => self.is_powered_on(),
+        }
+    }
+
+    /// Pops the next error from the controller's error queue.
+    /// Returns `None` once the queue is empty.
+    pub fn read_next_error(&mut self) -> Result<Option<RobotError>> {
+        let code = self.request_u8(Command::ReadNextError, vec![], self.timeout)?;
+        match RobotError::from_code(code) {
+            Some(error) if self.overload_guard && error.is_overload() => {
+                self.release_all_servos()?;
+                Err(Error::OverloadProtection(error))
+            }
+            error => Ok(error),
         }
     }
 
+    /// Releases all servos so the arm can be moved by hand.
+    pub fn release_all_servos(&mut self) -> Result<()> {
+        self.write_command(Command::ReleaseAllServos, vec![])
+    }
+
+    /// Releases a single joint (1-6) so it can be moved by hand.
+    pub fn release_servo(&mut self, joint: u8) -> Result<()> {
+        check_index("joint", joint, self.model.dof())?;
+        self.write_command(Command::ReleaseServo, vec![joint])
+    }
+
+    /// Re-enables the torque of a single joint (1-6) released by `release_servo`.
+    pub fn focus_servo(&mut self, joint: u8) -> Result<()> {
+        check_index("joint", joint, self.model.dof())?;
+        self.write_command(Command::FocusServo, vec![joint])
+    }
+
+    /// Stores the current position of a joint (1-6) as its zero position.
+    /// Requires `set_allow_calibration(true)`.
+    pub fn set_servo_calibration(&mut self, joint: u8) -> Result<()> {
+        check_index("joint", joint, self.model.dof())?;
+        if !self.allow_calibration {
+            return Err(Error::InvalidParameter("calibration is not allowed".into()));
+        }
+        self.write_command(Command::SetServoCalibration, vec![joint])
+    }
+
+    /// Selects how a motion command arriving during a motion is handled:
+    /// `true` interrupts the current motion and executes the newest command,
+    /// `false` queues it behind the current one, e.g. for streaming waypoints.
+    pub fn set_fresh_mode(&mut self, interrupt: bool) -> Result<()> {
+        self.write_command(Command::SetFreshMode, vec![interrupt as u8])
+    }
+
+    /// Whether new motion commands interrupt the current one, see `set_fresh_mode`.
+    pub fn get_fresh_mode(&mut self) -> Result<bool> {
+        self.request_bool(Command::GetFreshMode, vec![], self.timeout)
+    }
+
+    /// Enables or disables free-move (drag-teach) mode, in which servo torque
+    /// is released so the arm can be guided by hand.
+    pub fn set_free_move_mode(&mut self, enabled: bool) -> Result<()> {
+        self.write_command(Command::SetFreeMoveMode, vec![enabled as u8])
+    }
+
+    pub fn is_free_move_mode(&mut self) -> Result<bool> {
+        self.request_bool(Command::IsFreeMoveMode, vec![], self.timeout)
+    }
+
+    /// Checks whether the Atom/Basic controller link is alive.
+    pub fn is_controller_connected(&mut self) -> Result<bool> {
+        self.request_bool(Command::IsControllerConnected, vec![], self.timeout)
+    }
+
     // --- Atom IO ---
     
+    /// Lights the Atom's whole 5x5 LED matrix in one color. The firmware has
+    /// no command to set single pixels or patterns.
     pub fn set_led_color(&mut self, r: u8, g: u8, b: u8) -> Result<()> {
         self.write_command(Command::SetLedRgb, vec![r, g, b])
     }
 
-    // --- Movement ---
-    
-    /// Get current joint angles
-    pub fn get_angles(&mut self) -> Result<[f32; 6]> {
-        let response = self.request(Command::GetAngles, vec![], Duration::from_millis(500))?;
-        if response.len() != 12 {
-            return Err(Error::Protocol(format!("Expected 12 bytes for angles, got {}", response.len())));
-        }
-        
-        let mut angles = [0.0; 6];
-        for i in 0..6 {
-            let high = response[i * 2];
-            let low = response[i * 2 + 1];
-            let raw = (high as i16) << 8 | (low as i16); // Big endian
-            angles[i] = raw as f32 / 100.0;
-        }
-        Ok(angles)
+    pub fn set_led(&mut self, color: Color) -> Result<()> {
+        let (r, g, b) = color.rgb();
+        self.set_led_color(r, g, b)
     }
 
-    pub fn write_angles(&mut self, angles: [f32; 6], speed: u8) -> Result<()> {
-        let mut payload = Vec::with_capacity(13);
-        for &angle in &angles {
-            let value = (angle * 100.0) as i16;
-            let bytes = value.to_be_bytes();
-            payload.push(bytes[0]);
-            payload.push(bytes[1]);
+    /// Sets the LED from a hue in degrees and saturation and value in 0..=1.
+    pub fn set_led_hsv(&mut self, h: f32, s: f32, v: f32) -> Result<()> {
+        if !h.is_finite() || !(0.0..=1.0).contains(&s) || !(0.0..=1.0).contains(&v) {
+            return Err(Error::InvalidParameter(format!("invalid HSV color ({}, {}, {})", h, s, v)));
         }
-        payload.push(speed);
-        self.write_command(Command::WriteAngles, payload)
+        let (r, g, b) = hsv_to_rgb(h, s, v);
+        self.set_led_color(r, g, b)
     }
-    
-    pub fn get_coords(&mut self) -> Result<[f32; 6]> {
-         let response = self.request(Command::GetCoords, vec![], Duration::from_millis(500))?;
-        if response.len() != 12 {
-            return Err(Error::Protocol(format!("Expected 12 bytes for coords, got {}", response.len())));
-        }
-        
-        let mut coords = [0.0; 6];
-        // XYZ
-        for i in 0..3 {
-            let high = response[i * 2];
-            let low = response[i * 2 + 1];
-            let raw = (high as i16) << 8 | (low as i16);
-            coords[i] = raw as f32 / 10.0;
-        }
-        // RxRyRz
-        for i in 3..6 {
-            let high = response[i * 2];
-            let low = response[i * 2 + 1];
-            let raw = (high as i16) << 8 | (low as i16);
-            coords[i] = raw as f32 / 100.0;
+
+    /// Blinks the LED `times` times, `interval` on and `interval` off, ending off.
+    pub fn led_blink(&mut self, color: Color, times: u32, interval: Duration) -> Result<()> {
+        for _ in 0..times {
+            self.set_led(color)?;
+            self.clock.sleep(interval);
+            self.set_led(Color::Off)?;
+            self.clock.sleep(interval);
         }
-        Ok(coords)
+        Ok(())
     }
 
-    pub fn write_coords(&mut self, coords: [f32; 6], speed: u8, _mode: u8) -> Result<()> {
-        let mut payload = Vec::with_capacity(14);
-        // XYZ
-        for i in 0..3 {
-            let value = (coords[i] * 10.0) as i16;
-            let bytes = value.to_be_bytes();
-            payload.push(bytes[0]);
-            payload.push(bytes[1]);
+    /// Fades the LED linearly from `from` to `to` over `duration` in `steps` steps,
+    /// sending `steps + 1` colors including both ends.
+    pub fn led_fade(&mut self, from: Color, to: Color, duration: Duration, steps: u32) -> Result<()> {
+        if steps == 0 {
+            return Err(Error::InvalidParameter("fade needs at least one step".into()));
         }
-        // RxRyRz
-        for i in 3..6 {
-            let value = (coords[i] * 100.0) as i16;
-            let bytes = value.to_be_bytes();
-            payload.push(bytes[0]);
-            payload.push(bytes[1]);
+        let (from, to) = (from.rgb(), to.rgb());
+        let lerp = |a: u8, b: u8, t: f32| (a as f32 + (b as f32 - a as f32) * t).round() as u8;
+        for step in 0..=steps {
+            if step > 0 {
+                self.clock.sleep(duration / steps);
+            }
+            let t = step as f32 / steps as f32;
+            self.set_led_color(lerp(from.0, to.0, t), lerp(from.1, to.1, t), lerp(from.2, to.2, t))?;
         }
-        payload.push(speed);
-        payload.push(2); // Mode (MoveJ? Check docs, usually 2 for MyCobot)
-                            // C++ Code: command += static_cast<char>(2);
-                            // MyCobot.cpp:165
-        
-        self.write_command(Command::WriteCoords, payload)
+        Ok(())
+    }
+
+    /// Fades the LED from off up to `color` and back down over `duration`.
+    pub fn led_pulse(&mut self, color: Color, duration: Duration, steps: u32) -> Result<()> {
+        self.led_fade(Color::Off, color, duration / 2, steps)?;
+        self.led_fade(color, Color::Off, duration / 2, steps)
+    }
+
+    // --- Movement ---
+    
+    /// Get current joint angles
+    pub fn get_angles(&mut self) -> Result<JointAngles> {
+        self.get_angles_with_timeout(self.timeout)
+    }
+
+    pub fn get_angles_with_timeout(&mut self, timeout: Duration) -> Result<JointAngles> {
+        Ok(self.get_angles_array_with_timeout(timeout)?.into())
+    }
+
+    /// Like `get_angles`, but returns a plain array with joint 1 first.
+    pub fn get_angles_array(&mut self) -> Result<[f32; 6]> {
+        self.get_angles_array_with_timeout(self.timeout)
+    }
+
+    pub fn get_angles_array_with_timeout(&mut self, timeout: Duration) -> Result<[f32; 6]> {
+        let response = self.request(Command::GetAngles, vec![], timeout)?;
+        let angles = decode_angles(self.model, &response)?;
+        Ok(angles.map(|angle| self.angle_unit.convert_from_degrees(angle)))
+    }
+
+    /// Like `get_angles_array`, but also returns when the response arrived,
+    /// e.g. to tell whether a reading predates the last command. The firmware
+    /// doesn't number its frames, so this is the time of the clock set with
+    /// `set_clock`.
+    pub fn get_angles_timed(&mut self) -> Result<([f32; 6], Instant)> {
+        let angles = self.get_angles_array()?;
+        Ok((angles, self.clock.now()))
+    }
+
+    pub fn write_angles(&mut self, angles: impl Into<[f32; 6]>, speed: u8) -> Result<()> {
+        let payload = self.angles_payload(angles.into(), speed)?;
+        self.write_command(Command::WriteAngles, payload)
+    }
+
+    /// Like `write_angles`, but takes one angle per joint of the model, e.g.
+    /// four for the MyPalletizer, and fails with `Error::InvalidParameter` if
+    /// the length doesn't match.
+    pub fn write_angles_slice(&mut self, angles: &[f32], speed: u8) -> Result<()> {
+        let angles = self.padded("angles", angles)?;
+        self.write_angles(angles, speed)
+    }
+
+    /// Like `write_angles`, but waits for the controller to echo the command
+    /// as an acknowledgment and fails with `Error::Timeout` if it doesn't.
+    /// Only firmware that acks motion commands answers; the stock MyCobot 280
+    /// firmware doesn't, so this always times out there.
+    pub fn write_angles_acked(&mut self, angles: impl Into<[f32; 6]>, speed: u8) -> Result<()> {
+        let payload = self.angles_payload(angles.into(), speed)?;
+        self.request(Command::WriteAngles, payload, self.timeout)?;
+        Ok(())
+    }
+
+    fn angles_payload(&mut self, angles: [f32; 6], speed: u8) -> Result<Vec<u8>> {
+        let angles = angles.map(|angle| self.angle_unit.to_degrees(angle));
+        let joint_cap = self.joint_max_speeds[..self.model.dof()].iter().copied().min().unwrap_or(100);
+        let payload = angles_payload(
+            self.model,
+            self.clamp_mode,
+            &self.angle_limits,
+            angles,
+            speed,
+            self.max_speed.min(joint_cap),
+        )?;
+        self.check_powered()?;
+        Ok(payload)
+    }
+
+    /// Get the current angle of a single joint (1-6)
+    pub fn get_angle(&mut self, joint: u8) -> Result<f32> {
+        check_index("joint", joint, self.model.dof())?;
+        let angles = self.get_angles_array()?;
+        Ok(angles[joint as usize - 1])
+    }
+
+    /// Move a single joint (1-6) to the given angle
+    pub fn write_angle(&mut self, joint: u8, angle: f32, speed: u8) -> Result<()> {
+        check_speed(speed)?;
+        check_index("joint", joint, self.model.dof())?;
+        let angle = self.angle_unit.to_degrees(angle);
+        let index = joint as usize - 1;
+        let angle = apply_limit(self.clamp_mode, "joint", index, angle, self.angle_limits[index])?;
+        let bytes = encode_i16_be(scale_to_i16(angle, self.model.angle_scale())?);
+        let speed = self.capped_speed(speed, Some(joint));
+        self.check_powered()?;
+        self.write_command(Command::WriteAngle, vec![joint, bytes[0], bytes[1], speed])
+    }
+    
+    pub fn get_coords(&mut self) -> Result<Coords> {
+        self.get_coords_with_timeout(self.timeout)
+    }
+
+    pub fn get_coords_with_timeout(&mut self, timeout: Duration) -> Result<Coords> {
+        Ok(self.get_coords_array_with_timeout(timeout)?.into())
+    }
+
+    /// Like `get_coords`, but returns a plain `[x, y, z, rx, ry, rz]` array.
+    pub fn get_coords_array(&mut self) -> Result<[f32; 6]> {
+        self.get_coords_array_with_timeout(self.timeout)
+    }
+
+    /// Like `get_coords_array`, but also returns when the response arrived,
+    /// see `get_angles_timed`.
+    pub fn get_coords_timed(&mut self) -> Result<([f32; 6], Instant)> {
+        let coords = self.get_coords_array()?;
+        Ok((coords, self.clock.now()))
+    }
+
+    pub fn get_coords_array_with_timeout(&mut self, timeout: Duration) -> Result<[f32; 6]> {
+        let response = self.request(Command::GetCoords, vec![], timeout)?;
+        let mut coords = decode_coords(self.model, &response)?;
+        for rotation in &mut coords[3..] {
+            *rotation = self.angle_unit.convert_from_degrees(*rotation);
+        }
+        Ok(coords)
+    }
+
+    pub fn write_coords(&mut self, coords: impl Into<[f32; 6]>, speed: u8, mode: MoveMode) -> Result<()> {
+        let payload = self.coords_payload(coords.into(), speed, mode)?;
+        self.write_command(Command::WriteCoords, payload)
+    }
+
+    /// Like `write_coords`, but takes one value per axis of the model, e.g.
+    /// X, Y, Z and theta for the MyPalletizer, see `write_angles_slice`.
+    pub fn write_coords_slice(&mut self, coords: &[f32], speed: u8, mode: MoveMode) -> Result<()> {
+        let coords = self.padded("coords", coords)?;
+        self.write_coords(coords, speed, mode)
+    }
+
+    /// Like `write_coords`, but waits for the controller's acknowledgment,
+    /// see `write_angles_acked`.
+    pub fn write_coords_acked(&mut self, coords: impl Into<[f32; 6]>, speed: u8, mode: MoveMode) -> Result<()> {
+        let payload = self.coords_payload(coords.into(), speed, mode)?;
+        self.request(Command::WriteCoords, payload, self.timeout)?;
+        Ok(())
+    }
+
+    fn coords_payload(&mut self, mut coords: [f32; 6], speed: u8, mode: MoveMode) -> Result<Vec<u8>> {
+        for rotation in &mut coords[3..] {
+            *rotation = self.angle_unit.to_degrees(*rotation);
+        }
+        let payload =
+            coords_payload(self.model, self.clamp_mode, &self.coord_limits, coords, speed, self.max_speed, mode)?;
+        self.check_powered()?;
+        Ok(payload)
+    }
+
+    /// Get the current value of a single Cartesian axis, millimeters for X/Y/Z
+    /// and the angle unit for rotations.
+    pub fn get_coord(&mut self, axis: Axis) -> Result<f32> {
+        check_index("axis", axis as u8, self.model.dof())?;
+        let response = self.request(Command::GetCoord, vec![axis as u8], self.timeout)?;
+        let value = decode_i16_be(&response) as f32;
+        if axis.is_rotation() {
+            Ok(self.angle_unit.convert_from_degrees(value / self.model.rotation_scale()))
+        } else {
+            Ok(value / self.model.position_scale())
+        }
+    }
+
+    /// Move a single Cartesian axis to `value`, millimeters for X/Y/Z and the
+    /// angle unit for rotations.
+    pub fn write_coord(&mut self, axis: Axis, value: f32, speed: u8) -> Result<()> {
+        check_speed(speed)?;
+        check_index("axis", axis as u8, self.model.dof())?;
+        let value = if axis.is_rotation() { self.angle_unit.to_degrees(value) } else { value };
+        let value = apply_limit(self.clamp_mode, "axis", axis.index(), value, self.coord_limits[axis.index()])?;
+        let scale = if axis.is_rotation() { self.model.rotation_scale() } else { self.model.position_scale() };
+        let bytes = encode_i16_be(scale_to_i16(value, scale)?);
+        let speed = self.capped_speed(speed, None);
+        self.check_powered()?;
+        self.write_command(Command::WriteCoord, vec![axis as u8, bytes[0], bytes[1], speed])
+    }
+
+    // --- Coordinate frames ---
+
+    /// Sets the tool center point relative to the flange, so `get_coords` and
+    /// `write_coords` refer to the tip of a custom tool. Millimeters for X/Y/Z
+    /// and the angle unit for rotations.
+    pub fn set_tool_reference(&mut self, offset: impl Into<[f32; 6]>) -> Result<()> {
+        self.set_reference(Command::SetToolReference, offset.into())
+    }
+
+    /// Reads the tool center point set with `set_tool_reference`.
+    pub fn get_tool_reference(&mut self) -> Result<Coords> {
+        self.get_reference(Command::GetToolReference)
+    }
+
+    /// Sets the pose of the robot base in a world frame, so coordinates are
+    /// expressed relative to e.g. a workspace fixture instead of the base.
+    /// Units as in `set_tool_reference`.
+    pub fn set_world_reference(&mut self, origin: impl Into<[f32; 6]>) -> Result<()> {
+        self.set_reference(Command::SetWorldReference, origin.into())
+    }
+
+    /// Reads the world frame set with `set_world_reference`.
+    pub fn get_world_reference(&mut self) -> Result<Coords> {
+        self.get_reference(Command::GetWorldReference)
+    }
+
+    fn set_reference(&mut self, command: Command, mut coords: [f32; 6]) -> Result<()> {
+        for rotation in &mut coords[3..] {
+            *rotation = self.angle_unit.to_degrees(*rotation);
+        }
+        let payload = encode_coords(self.model, &coords)?;
+        self.write_command(command, payload)
+    }
+
+    fn get_reference(&mut self, command: Command) -> Result<Coords> {
+        let response = self.request(command, vec![], self.timeout)?;
+        let mut coords = decode_coords(self.model, &response)?;
+        for rotation in &mut coords[3..] {
+            *rotation = self.angle_unit.convert_from_degrees(*rotation);
+        }
+        Ok(coords.into())
+    }
+
+    // --- Jogging ---
+
+    /// Starts jogging a single joint (1-6) in the given direction (+1 or -1).
+    /// The robot keeps moving until `jog_stop` is sent.
+    pub fn jog_angle(&mut self, joint: u8, direction: i8, speed: u8) -> Result<()> {
+        check_speed(speed)?;
+        check_index("joint", joint, self.model.dof())?;
+        let direction = encode_direction(direction)?;
+        let speed = self.capped_speed(speed, Some(joint));
+        self.check_powered()?;
+        self.write_command(Command::JogAngle, vec![joint, direction, speed])
+    }
+
+    /// Starts jogging along a Cartesian axis (1-6 for X, Y, Z, Rx, Ry, Rz)
+    /// in the given direction (+1 or -1).
+    pub fn jog_coord(&mut self, axis: u8, direction: i8, speed: u8) -> Result<()> {
+        check_speed(speed)?;
+        check_index("axis", axis, self.model.dof())?;
+        let direction = encode_direction(direction)?;
+        let speed = self.capped_speed(speed, None);
+        self.check_powered()?;
+        self.write_command(Command::JogCoord, vec![axis, direction, speed])
+    }
+
+    /// Starts a jog session that sends `jog_stop` when it is dropped.
+    pub fn jog_session(&mut self) -> JogController<'_, P> {
+        JogController::new(self)
+    }
+
+    /// Stops any jog in progress.
+    pub fn jog_stop(&mut self) -> Result<()> {
+        self.write_command(Command::JogStop, vec![])
+    }
+
+    // --- Encoder ---
+
+    /// Sets the raw encoder value (0..=16383) of a single joint (1-6).
+    pub fn set_encoder(&mut self, joint: u8, value: u16) -> Result<()> {
+        check_index("joint", joint, self.model.dof())?;
+        check_encoder(value)?;
+        let bytes = value.to_be_bytes();
+        self.check_powered()?;
+        self.write_command(Command::SetEncoder, vec![joint, bytes[0], bytes[1]])
+    }
+
+    /// Reads the raw encoder value of a single joint (1-6).
+    pub fn get_encoder(&mut self, joint: u8) -> Result<u16> {
+        check_index("joint", joint, self.model.dof())?;
+        let response = self.request(Command::GetEncoder, vec![joint], self.timeout)?;
+        Ok(u16::from_be_bytes([response[0], response[1]]))
+    }
+
+    /// Sets the (offset, degrees per count) of each joint used by
+    /// `get_angles_from_encoders`. Defaults to `DEFAULT_ENCODER_CALIBRATION`.
+    pub fn set_encoder_calibration(&mut self, calibration: [(f32, f32); 6]) {
+        self.encoder_calibration = calibration;
+    }
+
+    /// Reads the joint angles from the raw encoders instead of `GetAngles`,
+    /// converting each as `(encoder - offset) * scale` with the encoder calibration.
+    /// Joints the model doesn't have are left at 0.
+    pub fn get_angles_from_encoders(&mut self) -> Result<[f32; 6]> {
+        let encoders = self.get_encoders()?;
+
+        let mut angles = [0.0; 6];
+        for i in 0..self.model.dof() {
+            let (offset, scale) = self.encoder_calibration[i];
+            let degrees = (encoders[i] as f32 - offset) * scale;
+            angles[i] = self.angle_unit.convert_from_degrees(degrees);
+        }
+        Ok(angles)
+    }
+
+    /// Moves all joints to the given raw encoder values.
+    pub fn set_encoders(&mut self, values: [u16; 6], speed: u8) -> Result<()> {
+        check_speed(speed)?;
+        let mut payload = Vec::with_capacity(13);
+        for &value in &values {
+            check_encoder(value)?;
+            payload.extend_from_slice(&value.to_be_bytes());
+        }
+        payload.push(speed);
+        self.check_powered()?;
+        self.write_command(Command::SetEncoders, payload)
+    }
+
+    /// Moves to `target` through `steps` evenly spaced waypoints, starting from
+    /// the current angles, sending one every `interval`. Waypoints are sent at the
+    /// speed cap from `set_max_speed`, 100 by default.
+    pub fn interpolate_move(&mut self, target: JointAngles, steps: usize, interval: Duration) -> Result<()> {
+        if steps == 0 {
+            return Err(Error::InvalidParameter("steps must be at least 1".into()));
+        }
+        let target: [f32; 6] = target.into();
+        let degrees = target.map(|angle| self.angle_unit.to_degrees(angle));
+        apply_limits(self.clamp_mode, "joint", degrees, &self.angle_limits)?;
+        let start = self.get_angles_array()?;
+
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            let mut waypoint = target;
+            for (value, &from) in waypoint.iter_mut().zip(&start) {
+                *value = from + (*value - from) * t;
+            }
+            self.write_angles(waypoint, self.max_speed)?;
+            self.clock.sleep(interval);
+        }
+        Ok(())
+    }
+
+    /// Moves the tool along a straight line to `target` by streaming linear
+    /// `write_coords` waypoints no more than `max_step_mm` apart, starting from
+    /// the current coordinates. Rotations are interpolated along with the
+    /// position. Use `set_fresh_mode(false)` so the waypoints queue instead of
+    /// interrupting each other. Fails with `Error::InvalidParameter` if the
+    /// move would take more than `MAX_LINEAR_STEPS` waypoints.
+    pub fn move_linear(&mut self, target: Coords, speed: u8, max_step_mm: f32) -> Result<()> {
+        check_speed(speed)?;
+        if !(max_step_mm.is_finite() && max_step_mm > 0.0) {
+            return Err(Error::InvalidParameter(format!("step must be positive, got {}", max_step_mm)));
+        }
+        let target: [f32; 6] = target.into();
+        check_all_finite(&target)?;
+        let start = self.get_coords_array()?;
+
+        let distance = (0..3).map(|i| (target[i] - start[i]).powi(2)).sum::<f32>().sqrt();
+        let steps = (distance / max_step_mm).ceil();
+        if steps > MAX_LINEAR_STEPS as f32 {
+            return Err(Error::InvalidParameter(format!(
+                "{} mm in steps of {} mm takes more than {} waypoints",
+                distance, max_step_mm, MAX_LINEAR_STEPS
+            )));
+        }
+        let steps = (steps as usize).max(1);
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            let mut waypoint = target;
+            for (value, &from) in waypoint.iter_mut().zip(&start) {
+                *value = from + (*value - from) * t;
+            }
+            self.write_coords(waypoint, speed, MoveMode::Linear)?;
+        }
+        Ok(())
+    }
+
+    /// Moves all joints to the model's home pose, see `RobotModel::home_angles`.
+    pub fn home(&mut self, speed: u8) -> Result<()> {
+        let home = self.model.home_angles().map(|angle| self.angle_unit.convert_from_degrees(angle));
+        self.write_angles(home, speed)
+    }
+
+    /// Like `home`, but waits until the motion is done.
+    pub fn home_sync(&mut self, speed: u8) -> Result<()> {
+        self.home(speed)?;
+        self.sync_move(MOVE_POLL_INTERVAL, MOVE_TIMEOUT)
+    }
+
+    /// Moves all joints to raw encoder values and waits until the motion is done.
+    ///
+    /// `SetEncoders` already interpolates all joints so they arrive together;
+    /// the firmware has no separate synchronized-move opcode. Bypasses the
+    /// angle scaling and limits, e.g. for calibration.
+    pub fn write_encoders_sync(&mut self, encoders: [u16; 6], speed: u8) -> Result<()> {
+        self.set_encoders(encoders, speed)?;
+        self.sync_move(MOVE_POLL_INTERVAL, MOVE_TIMEOUT)
+    }
+
+    /// Reads the raw encoder values of all joints.
+    /// Joints the model doesn't have are left at 0.
+    pub fn get_encoders(&mut self) -> Result<[u16; 6]> {
+        let response = self.request(Command::GetEncoders, vec![], self.timeout)?;
+
+        let mut values = [0; 6];
+        for (value, pair) in values.iter_mut().zip(response.chunks_exact(2)) {
+            *value = u16::from_be_bytes([pair[0], pair[1]]);
+        }
+        Ok(values)
+    }
+
+    // --- Servo diagnostics ---
+
+    /// Reads the current drawn by each servo in milliamperes, joint 1 first.
+    /// The controller sends one big-endian 16-bit value per servo, joints the
+    /// model doesn't have are left at 0 here and in the other diagnostics.
+    pub fn get_servo_currents(&mut self) -> Result<[f32; 6]> {
+        let response = self.request(Command::GetServoCurrents, vec![], self.timeout)?;
+
+        let mut values = [0.0; 6];
+        for (value, pair) in values.iter_mut().zip(response.chunks_exact(2)) {
+            *value = decode_i16_be(pair) as f32;
+        }
+        Ok(values)
+    }
+
+    /// Reads the fault flags of each servo, joint 1 first.
+    /// The controller sends one status byte per servo.
+    pub fn get_servo_status(&mut self) -> Result<[ServoStatus; 6]> {
+        let response = self.request(Command::GetServoStatus, vec![], self.timeout)?;
+
+        let mut status = [ServoStatus::default(); 6];
+        for (value, &bits) in status.iter_mut().zip(&response) {
+            *value = ServoStatus::from_bits(bits);
+        }
+        Ok(status)
+    }
+
+    /// Reads the temperature of each servo in degrees Celsius, joint 1 first.
+    /// The controller sends one byte per servo.
+    pub fn get_servo_temperatures(&mut self) -> Result<[f32; 6]> {
+        let response = self.request(Command::GetServoTemps, vec![], self.timeout)?;
+
+        let mut values = [0.0; 6];
+        for (value, &raw) in values.iter_mut().zip(&response) {
+            *value = raw as f32;
+        }
+        Ok(values)
+    }
+
+    // --- Speed ---
+
+    /// Sets the global speed as a percentage (0..=100).
+    pub fn set_speed(&mut self, percentage: u8) -> Result<()> {
+        check_speed(percentage)?;
+        self.write_command(Command::SetSpeed, vec![percentage])
+    }
+
+    /// Reads the global speed percentage.
+    pub fn get_speed(&mut self) -> Result<u8> {
+        self.request_u8(Command::GetSpeed, vec![], self.timeout)
+    }
+
+    // --- Gripper ---
+
+    /// Makes the gripper commands address a gripper of the given type by
+    /// appending it to `set_gripper_value`, `get_gripper_value` and
+    /// `set_gripper_state`. Until it's set, no type is sent and the firmware
+    /// assumes the adaptive gripper. See `get_gripper_type` for the attached one.
+    pub fn set_gripper_type(&mut self, gripper_type: GripperType) {
+        self.gripper_type = Some(gripper_type);
+    }
+
+    /// The gripper type set with `set_gripper_type`.
+    pub fn gripper_type(&self) -> Option<GripperType> {
+        self.gripper_type
+    }
+
+    /// Asks the controller which gripper is attached. Returns `None` if it
+    /// reports no gripper or one of an unknown type.
+    pub fn get_gripper_type(&mut self) -> Result<Option<GripperType>> {
+        let code = self.request_u8(Command::GetGripperType, vec![], self.timeout)?;
+        Ok(GripperType::from_code(code))
+    }
+
+    fn gripper_payload(&self, mut payload: Vec<u8>) -> Vec<u8> {
+        payload.extend(self.gripper_type.map(u8::from));
+        payload
+    }
+
+    /// Moves the gripper to the given opening (0 = closed, 100 = open).
+    pub fn set_gripper_value(&mut self, value: u8, speed: u8) -> Result<()> {
+        check_speed(speed)?;
+        if value > 100 {
+            return Err(Error::InvalidParameter(format!("gripper value must be in 0..=100, got {}", value)));
+        }
+        let payload = self.gripper_payload(vec![value, speed]);
+        self.write_command(Command::SetGripperValue, payload)
+    }
+
+    /// Reads the current gripper opening (0 = closed, 100 = open).
+    pub fn get_gripper_value(&mut self) -> Result<u8> {
+        let payload = self.gripper_payload(vec![]);
+        self.request_u8(Command::GetGripperValue, payload, self.timeout)
+    }
+
+    /// Fully opens or closes the gripper.
+    pub fn set_gripper_state(&mut self, open: bool, speed: u8) -> Result<()> {
+        check_speed(speed)?;
+        // The firmware uses 0 for open and 1 for closed
+        let state = if open { 0 } else { 1 };
+        let payload = self.gripper_payload(vec![state, speed]);
+        self.write_command(Command::GripperMode, payload)
+    }
+
+    pub fn is_gripper_moving(&mut self) -> Result<bool> {
+        self.request_bool(Command::IsGripperMoving, vec![], self.timeout)
+    }
+
+    // --- Digital IO ---
+
+    pub fn set_pin_mode(&mut self, pin: u8, mode: PinMode) -> Result<()> {
+        self.write_command(Command::SetPinMode, vec![pin, mode.into()])
+    }
+
+    pub fn set_digital_output(&mut self, pin: u8, level: bool) -> Result<()> {
+        self.write_command(Command::SetDigitalOut, vec![pin, level as u8])
+    }
+
+    pub fn get_digital_input(&mut self, pin: u8) -> Result<bool> {
+        self.request_bool(Command::GetDigitalIn, vec![pin], self.timeout)
+    }
+
+    /// Reads all `ATOM_GPIO_PINS` into a bitmask, bit `i` holding the state of
+    /// `ATOM_GPIO_PINS[i]`. Decode it with `pin_state`.
+    ///
+    /// The firmware has no bulk read, so this is one request per pin.
+    pub fn get_digital_inputs(&mut self) -> Result<u16> {
+        let mut mask = 0;
+        for (bit, &pin) in ATOM_GPIO_PINS.iter().enumerate() {
+            if self.get_digital_input(pin)? {
+                mask |= 1 << bit;
+            }
+        }
+        Ok(mask)
+    }
+
+    // --- Basic IO ---
+
+    /// Sets an output pin on the M5 Basic base (separate pin space from the Atom).
+    pub fn set_basic_output(&mut self, pin: u8, level: bool) -> Result<()> {
+        self.write_command(Command::SetBasicOut, vec![pin, level as u8])
+    }
+
+    /// Reads an input pin on the M5 Basic base.
+    pub fn get_basic_input(&mut self, pin: u8) -> Result<bool> {
+        self.request_bool(Command::GetBasicIn, vec![pin], self.timeout)
+    }
+
+    // --- Motion Status ---
+
+    /// Checks whether the robot has reached `target`.
+    /// `target` is interpreted as coordinates if `is_linear` is set, otherwise as joint angles.
+    pub fn is_in_position(&mut self, target: &[f32; 6], is_linear: bool) -> Result<bool> {
+        let mut payload = if is_linear {
+            encode_coords(self.model, target)?
+        } else {
+            encode_angles(self.model, target)?
+        };
+        payload.push(is_linear as u8);
+        self.request_bool(Command::IsInPosition, payload, self.timeout)
+    }
+
+    /// Checks whether the robot is currently executing a motion.
+    pub fn is_moving(&mut self) -> Result<bool> {
+        self.request_bool(Command::CheckRunning, vec![], self.timeout)
+    }
+
+    /// Blocks until the robot reports it is no longer moving.
+    /// Polls `is_moving` every `poll_interval` and returns `Error::Timeout`
+    /// if the robot is still moving after `timeout`.
+    pub fn sync_move(&mut self, poll_interval: Duration, timeout: Duration) -> Result<()> {
+        let start = self.clock.now();
+        while self.is_moving()? {
+            if self.clock.now() - start > timeout {
+                return Err(Error::Timeout);
+            }
+            self.clock.sleep(poll_interval);
+        }
+        Ok(())
+    }
+
+    // --- Program Control ---
+
+    /// Pauses the current motion.
+    pub fn pause(&mut self) -> Result<()> {
+        self.write_command(Command::ProgramPause, vec![])
+    }
+
+    /// Resumes a paused motion.
+    pub fn resume(&mut self) -> Result<()> {
+        self.write_command(Command::ProgramResume, vec![])
+    }
+
+    pub fn is_paused(&mut self) -> Result<bool> {
+        self.request_bool(Command::IsProgramPaused, vec![], self.timeout)
+    }
+
+    /// Queries power, motion, pause and error state in one call, e.g. for a dashboard.
+    pub fn get_status(&mut self) -> Result<RobotStatus> {
+        Ok(RobotStatus {
+            powered: self.is_powered_on()?,
+            moving: self.is_moving()?,
+            paused: self.is_paused()?,
+            pending_error: self.read_next_error()?,
+        })
+    }
+
+    /// Aborts the current motion.
+    pub fn stop(&mut self) -> Result<()> {
+        self.write_command(Command::TaskStop, vec![])
+    }
+
+    /// Leaves the arm in a safe state before closing the connection: aborts
+    /// any motion, powers off and flushes the port. Dropping `MyCobot` doesn't
+    /// do this, so long-running services should call it on exit.
+    pub fn shutdown(&mut self) -> Result<()> {
+        self.stop()?;
+        self.power_off()?;
+        SerialPort::flush(&mut self.port)?;
+        Ok(())
+    }
+
+    /// Moves through `points` of (target, speed, dwell): writes each target,
+    /// waits for the motion to finish and dwells before the next one.
+    /// Stops at the first error, e.g. a controller error or a motion that
+    /// doesn't finish within `MOVE_TIMEOUT`. A motion that ends short of its
+    /// target, because it was stopped or paused, aborts the trajectory with
+    /// `Error::MotionStopped`.
+    pub fn play_trajectory(&mut self, points: &[(JointAngles, u8, Duration)]) -> Result<()> {
+        for &(angles, speed, dwell) in points {
+            let mut payload = self.angles_payload(angles.into(), speed)?;
+            self.write_command(Command::WriteAngles, payload.clone())?;
+            self.sync_move(MOVE_POLL_INTERVAL, MOVE_TIMEOUT)?;
+
+            // Same target as written, with the joint-space flag instead of the speed
+            payload.pop();
+            payload.push(0);
+            if !self.request_bool(Command::IsInPosition, payload, self.timeout)? {
+                return Err(Error::MotionStopped);
+            }
+            self.clock.sleep(dwell);
+        }
+        Ok(())
+    }
+}
+
+/// Reports a frame that is about to be written to the observer, or to the
+/// debug log if there is none.
+fn report_frame(observer: &mut Option<FrameObserver>, debug_mode: bool, command: Command, bytes: &[u8]) {
+    #[cfg(feature = "tracing")]
+    tracing::trace!(direction = "tx", command = ?command, frame = ?bytes, "frame");
+    if let Some(observer) = observer {
+        observer(Direction::Tx, bytes);
+    } else if debug_mode {
+        debug!("Writing {:?}: {:02X?}", command, bytes);
+    }
+}
+
+/// Checks that `packet` is the response to a request for `command`. Fails with
+/// `Error::LengthMismatch` for the right command with a malformed payload,
+/// `Error::Controller` for an error frame and `Error::UnexpectedCommand` for
+/// any other packet, e.g. an unsolicited message or the late response to an
+/// earlier request.
+pub(crate) fn parse_response(model: RobotModel, command: Command, packet: &Packet) -> Result<()> {
+    if packet.command == command {
+        return check_response_len(model, command, &packet.payload);
+    }
+    match controller_error(packet) {
+        Some(error) => Err(Error::Controller(error)),
+        None => Err(Error::UnexpectedCommand { expected: command, got: packet.command }),
+    }
+}
+
+/// Returns the error carried by an error frame (`ReadNextError` with a non-zero
+/// code) that arrives while waiting for the response to another command.
+fn controller_error(packet: &Packet) -> Option<RobotError> {
+    match (packet.command, packet.payload.as_slice()) {
+        (Command::ReadNextError, &[code]) => RobotError::from_code(code),
+        _ => None,
+    }
+}
+
+/// Validates a response payload against `Command::expected_response_len`,
+/// with angle and coordinate frames sized by the model's joint count.
+fn check_response_len(model: RobotModel, command: Command, payload: &[u8]) -> Result<()> {
+    let expected = match command {
+        Command::GetAngles
+        | Command::GetCoords
+        | Command::GetToolReference
+        | Command::GetWorldReference
+        | Command::GetEncoders
+        | Command::GetServoCurrents => Some(model.dof() * 2),
+        // One byte per servo
+        Command::GetServoStatus | Command::GetServoTemps => Some(model.dof()),
+        _ => command.expected_response_len(),
+    };
+    match expected {
+        Some(len) if payload.len() != len => Err(Error::LengthMismatch { expected: len, got: payload.len() }),
+        _ => Ok(()),
+    }
+}
+
+/// Decodes joint angles from a response payload of 2 bytes per joint.
+/// Joints the model doesn't have are left at 0.
+pub(crate) fn decode_angles(model: RobotModel, response: &[u8]) -> Result<[f32; 6]> {
+    if response.len() != model.dof() * 2 {
+        return Err(Error::LengthMismatch { expected: model.dof() * 2, got: response.len() });
+    }
+
+    let mut angles = [0.0; 6];
+    for i in 0..model.dof() {
+        angles[i] = decode_i16_be(&response[i * 2..]) as f32 / model.angle_scale();
+    }
+    Ok(angles)
+}
+
+/// Decodes coordinates from a response payload of 2 bytes per axis:
+/// XYZ first, then the model's rotations.
+pub(crate) fn decode_coords(model: RobotModel, response: &[u8]) -> Result<[f32; 6]> {
+    if response.len() != model.dof() * 2 {
+        return Err(Error::LengthMismatch { expected: model.dof() * 2, got: response.len() });
+    }
+
+    let mut coords = [0.0; 6];
+    for i in 0..model.dof() {
+        let raw = decode_i16_be(&response[i * 2..]);
+        let scale = if i < 3 { model.position_scale() } else { model.rotation_scale() };
+        coords[i] = raw as f32 / scale;
+    }
+    Ok(coords)
+}
+
+/// Scales `value` to a fixed-point `i16`, truncating towards zero.
+/// Fails instead of saturating if the result does not fit.
+pub(crate) fn scale_to_i16(value: f32, scale: f32) -> Result<i16> {
+    let scaled = (value * scale).trunc();
+    if (i16::MIN as f32..=i16::MAX as f32).contains(&scaled) {
+        Ok(scaled as i16)
+    } else {
+        Err(Error::InvalidParameter(format!("{} does not fit the wire format (scale {})", value, scale)))
+    }
+}
+
+/// Encodes the model's joint angles as big-endian `i16` in hundredths of a degree.
+pub(crate) fn encode_angles(model: RobotModel, angles: &[f32; 6]) -> Result<Vec<u8>> {
+    check_all_finite(angles)?;
+    let mut payload = Vec::with_capacity(14);
+    for &angle in &angles[..model.dof()] {
+        let value = scale_to_i16(angle, model.angle_scale())?;
+        payload.extend_from_slice(&encode_i16_be(value));
+    }
+    Ok(payload)
+}
+
+/// Encodes the model's coordinates as big-endian `i16`: XYZ in tenths of a
+/// millimeter, the remaining rotations in hundredths of a degree.
+pub(crate) fn encode_coords(model: RobotModel, coords: &[f32; 6]) -> Result<Vec<u8>> {
+    check_all_finite(coords)?;
+    let mut payload = Vec::with_capacity(14);
+    // XYZ
+    for &coord in &coords[..3] {
+        let value = scale_to_i16(coord, model.position_scale())?;
+        payload.extend_from_slice(&encode_i16_be(value));
+    }
+    // RxRyRz or theta
+    for &coord in &coords[3..model.dof()] {
+        let value = scale_to_i16(coord, model.rotation_scale())?;
+        payload.extend_from_slice(&encode_i16_be(value));
+    }
+    Ok(payload)
+}
+
+/// Validates a 1-based joint or axis index.
+fn check_index(name: &str, index: u8, count: usize) -> Result<()> {
+    if (1..=count).contains(&(index as usize)) {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter(format!("{} must be in 1..={}, got {}", name, count, index)))
+    }
+}
+
+/// Rejects NaN and infinity, e.g. from failed upstream math, which would
+/// otherwise be truncated to a valid-looking target.
+fn check_finite(index: usize, value: f32) -> Result<()> {
+    if value.is_finite() {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter(format!("non-finite value at index {}", index)))
+    }
+}
+
+fn check_all_finite(values: &[f32; 6]) -> Result<()> {
+    for (i, &value) in values.iter().enumerate() {
+        check_finite(i, value)?;
+    }
+    Ok(())
+}
+
+/// Checks a value against the (min, max) limit of the 0-based joint/axis `index`.
+fn check_limit(name: &str, index: usize, value: f32, (min, max): (f32, f32)) -> Result<()> {
+    check_finite(index, value)?;
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter(format!(
+            "{} {} value {} outside {}..={}",
+            name,
+            index + 1,
+            value,
+            min,
+            max
+        )))
+    }
+}
+
+/// Checks a value against its limit like `check_limit`, or clamps it to the
+/// limit in `ClampMode::Clamp`.
+fn apply_limit(mode: ClampMode, name: &str, index: usize, value: f32, limit: (f32, f32)) -> Result<f32> {
+    match mode {
+        ClampMode::Reject => check_limit(name, index, value, limit).map(|()| value),
+        ClampMode::Clamp => {
+            check_finite(index, value)?;
+            let clamped = value.clamp(limit.0, limit.1);
+            if clamped != value {
+                warn!("Clamped {} {} value {} to {}", name, index + 1, value, clamped);
+            }
+            Ok(clamped)
+        }
+    }
+}
+
+fn apply_limits(mode: ClampMode, name: &str, mut values: [f32; 6], limits: &[(f32, f32); 6]) -> Result<[f32; 6]> {
+    for (i, (value, &limit)) in values.iter_mut().zip(limits).enumerate() {
+        *value = apply_limit(mode, name, i, *value, limit)?;
+    }
+    Ok(values)
+}
+
+/// Builds a `WriteAngles` payload from angles in degrees: checks the speed,
+/// applies the joint `limits` and appends the speed capped to `max_speed`.
+pub(crate) fn angles_payload(
+    model: RobotModel,
+    clamp_mode: ClampMode,
+    limits: &[(f32, f32); 6],
+    angles: [f32; 6],
+    speed: u8,
+    max_speed: u8,
+) -> Result<Vec<u8>> {
+    check_speed(speed)?;
+    let angles = apply_limits(clamp_mode, "joint", angles, limits)?;
+    let mut payload = encode_angles(model, &angles)?;
+    payload.push(speed.min(max_speed));
+    Ok(payload)
+}
+
+/// Like `angles_payload`, for a `WriteCoords` payload with millimeters and
+/// degrees, followed by the move mode.
+pub(crate) fn coords_payload(
+    model: RobotModel,
+    clamp_mode: ClampMode,
+    limits: &[(f32, f32); 6],
+    coords: [f32; 6],
+    speed: u8,
+    max_speed: u8,
+    mode: MoveMode,
+) -> Result<Vec<u8>> {
+    check_speed(speed)?;
+    let coords = apply_limits(clamp_mode, "axis", coords, limits)?;
+    let mut payload = encode_coords(model, &coords)?;
+    payload.push(speed.min(max_speed));
+    payload.push(mode.into());
+    Ok(payload)
+}
+
+/// Speeds are percentages of the maximum.
+pub(crate) fn check_speed(speed: u8) -> Result<()> {
+    if speed <= 100 {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter(format!("speed must be in 0..=100, got {}", speed)))
+    }
+}
+
+/// The firmware expects 1 for positive and 0 for negative jog direction.
+fn encode_direction(direction: i8) -> Result<u8> {
+    match direction {
+        1 => Ok(1),
+        -1 => Ok(0),
+        d => Err(Error::InvalidParameter(format!("direction must be 1 or -1, got {}", d))),
+    }
+}
+
+fn check_encoder(value: u16) -> Result<()> {
+    if value <= MAX_ENCODER {
+        Ok(())
+    } else {
+        Err(Error::InvalidParameter(format!("encoder value must be in 0..={}, got {}", MAX_ENCODER, value)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_response() {
+        let model = RobotModel::MyCobot280;
+        let response = Packet::new(Command::GetSpeed, vec![50]);
+        assert!(parse_response(model, Command::GetSpeed, &response).is_ok());
+
+        // Right command, but too short to be the answer
+        let truncated = Packet::new(Command::GetAngles, vec![0; 10]);
+        assert!(matches!(
+            parse_response(model, Command::GetAngles, &truncated),
+            Err(Error::LengthMismatch { expected: 12, got: 10 })
+        ));
+
+        let error = Packet::new(Command::ReadNextError, vec![0x20]);
+        assert!(matches!(
+            parse_response(model, Command::GetAngles, &error),
+            Err(Error::Controller(RobotError::NoSolution))
+        ));
+
+        let other = Packet::new(Command::GetCoords, vec![0; 12]);
+        assert!(matches!(
+            parse_response(model, Command::GetAngles, &other),
+            Err(Error::UnexpectedCommand { expected: Command::GetAngles, got: Command::GetCoords })
+        ));
+    }
+
+    #[test]
+    fn test_scale_to_i16_bounds() {
+        assert_eq!(scale_to_i16(327.67, 100.0).unwrap(), i16::MAX);
+        assert_eq!(scale_to_i16(-327.68, 100.0).unwrap(), i16::MIN);
+        assert_eq!(scale_to_i16(3276.7, 10.0).unwrap(), i16::MAX);
+        assert!(scale_to_i16(327.68, 100.0).is_err());
+        assert!(scale_to_i16(-327.69, 100.0).is_err());
+        assert!(scale_to_i16(f32::NAN, 100.0).is_err());
+        assert!(scale_to_i16(f32::INFINITY, 10.0).is_err());
+    }
+
+    #[test]
+    fn test_encode_angles_overflow() {
+        let model = RobotModel::MyCobot280;
+        assert!(encode_angles(model, &[0.0, 0.0, 0.0, 0.0, 0.0, 400.0]).is_err());
+        assert_eq!(encode_angles(model, &[-1.0, 0.0, 0.0, 0.0, 0.0, 0.0]).unwrap()[..2], [0xFF, 0x9C]);
+    }
+
+    #[test]
+    fn test_encode_per_model() {
+        let angles = [1.0, 2.0, 3.0, 4.0, 5.0, 6.0];
+        for model in [RobotModel::MyCobot280, RobotModel::MyCobotPro320, RobotModel::MechArm270] {
+            assert_eq!(encode_angles(model, &angles).unwrap().len(), 12);
+            assert_eq!(encode_coords(model, &angles).unwrap().len(), 12);
+        }
+        let palletizer = RobotModel::MyPalletizer260;
+        assert_eq!(
+            encode_angles(palletizer, &angles).unwrap(),
+            vec![0x00, 0x64, 0x00, 0xC8, 0x01, 0x2C, 0x01, 0x90]
+        );
+        assert_eq!(
+            encode_coords(palletizer, &angles).unwrap(),
+            vec![0x00, 0x0A, 0x00, 0x14, 0x00, 0x1E, 0x01, 0x90]
+        );
+    }
+
+    #[test]
+    fn test_decode_per_model() {
+        let payload = [0x00, 0x64, 0x00, 0xC8, 0xFF, 0x9C, 0x01, 0x90];
+        let palletizer = RobotModel::MyPalletizer260;
+        assert_eq!(decode_angles(palletizer, &payload).unwrap(), [1.0, 2.0, -1.0, 4.0, 0.0, 0.0]);
+        assert_eq!(decode_coords(palletizer, &payload).unwrap(), [10.0, 20.0, -10.0, 4.0, 0.0, 0.0]);
+        assert!(decode_angles(RobotModel::MyCobotPro320, &payload).is_err());
+        assert!(decode_angles(palletizer, &[0; 12]).is_err());
     }
 }
@@ -0,0 +1,296 @@
+/*
+ * Copyright (C) 2026 Progress Robotics UG
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+/// Joint angles, in degrees unless another `AngleUnit` is selected.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct JointAngles {
+    pub j1: f32,
+    pub j2: f32,
+    pub j3: f32,
+    pub j4: f32,
+    pub j5: f32,
+    pub j6: f32,
+}
+
+/// Cartesian pose: position in millimeters, orientation in degrees unless
+/// another `AngleUnit` is selected.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Coords {
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+    pub rx: f32,
+    pub ry: f32,
+    pub rz: f32,
+}
+
+/// A single Cartesian axis, numbered 1-6 on the wire.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Axis {
+    X = 1,
+    Y = 2,
+    Z = 3,
+    Rx = 4,
+    Ry = 5,
+    Rz = 6,
+}
+
+impl Axis {
+    /// Position of the axis in `[x, y, z, rx, ry, rz]` arrays.
+    pub fn index(self) -> usize {
+        self as usize - 1
+    }
+
+    /// Whether the axis is an orientation in degrees rather than a position in millimeters.
+    pub fn is_rotation(self) -> bool {
+        matches!(self, Axis::Rx | Axis::Ry | Axis::Rz)
+    }
+}
+
+/// Unit of joint angles and rotations in the robot API. The wire format is
+/// always scaled degrees.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AngleUnit {
+    #[default]
+    Degrees,
+    Radians,
+}
+
+impl AngleUnit {
+    /// Converts `value` in this unit to degrees.
+    pub fn to_degrees(self, value: f32) -> f32 {
+        match self {
+            AngleUnit::Degrees => value,
+            AngleUnit::Radians => value.to_degrees(),
+        }
+    }
+
+    /// Converts `degrees` to this unit.
+    pub fn convert_from_degrees(self, degrees: f32) -> f32 {
+        match self {
+            AngleUnit::Degrees => degrees,
+            AngleUnit::Radians => degrees.to_radians(),
+        }
+    }
+}
+
+/// What motion commands do with angles and coordinates outside the limits.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ClampMode {
+    /// Fail with `Error::InvalidParameter`.
+    #[default]
+    Reject,
+    /// Move to the nearest limit instead and log a warning.
+    Clamp,
+}
+
+/// Fault flags reported by a servo, bit for bit as in the servo's status register.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct ServoStatus(u8);
+
+impl ServoStatus {
+    pub const VOLTAGE: u8 = 1 << 0;
+    pub const SENSOR: u8 = 1 << 1;
+    pub const OVERHEAT: u8 = 1 << 2;
+    pub const OVERCURRENT: u8 = 1 << 3;
+    pub const ANGLE: u8 = 1 << 4;
+    pub const OVERLOAD: u8 = 1 << 5;
+
+    pub fn from_bits(bits: u8) -> Self {
+        Self(bits)
+    }
+
+    pub fn bits(self) -> u8 {
+        self.0
+    }
+
+    /// Whether no fault is reported.
+    pub fn is_ok(self) -> bool {
+        self.0 == 0
+    }
+
+    /// Supply voltage out of range.
+    pub fn voltage_error(self) -> bool {
+        self.0 & Self::VOLTAGE != 0
+    }
+
+    /// Position sensor (magnetic encoder) failure.
+    pub fn sensor_error(self) -> bool {
+        self.0 & Self::SENSOR != 0
+    }
+
+    pub fn overheat(self) -> bool {
+        self.0 & Self::OVERHEAT != 0
+    }
+
+    pub fn overcurrent(self) -> bool {
+        self.0 & Self::OVERCURRENT != 0
+    }
+
+    /// Target angle outside the servo's range.
+    pub fn angle_error(self) -> bool {
+        self.0 & Self::ANGLE != 0
+    }
+
+    /// Sustained overload, e.g. a stalled joint.
+    pub fn overload(self) -> bool {
+        self.0 & Self::OVERLOAD != 0
+    }
+}
+
+/// Named colors for the Atom LED.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Blue,
+    Yellow,
+    Cyan,
+    Magenta,
+    White,
+    Off,
+}
+
+impl Color {
+    /// The (r, g, b) value sent to the LED.
+    pub fn rgb(self) -> (u8, u8, u8) {
+        match self {
+            Color::Red => (255, 0, 0),
+            Color::Green => (0, 255, 0),
+            Color::Blue => (0, 0, 255),
+            Color::Yellow => (255, 255, 0),
+            Color::Cyan => (0, 255, 255),
+            Color::Magenta => (255, 0, 255),
+            Color::White => (255, 255, 255),
+            Color::Off => (0, 0, 0),
+        }
+    }
+}
+
+/// Converts a hue in degrees (wrapped to 0..360) and saturation and value
+/// in 0..=1 to (r, g, b).
+#[cfg(feature = "std")]
+pub(crate) fn hsv_to_rgb(h: f32, s: f32, v: f32) -> (u8, u8, u8) {
+    let c = v * s;
+    let h = h.rem_euclid(360.0) / 60.0;
+    let x = c * (1.0 - (h % 2.0 - 1.0).abs());
+    let (r, g, b) = match h as u8 {
+        0 => (c, x, 0.0),
+        1 => (x, c, 0.0),
+        2 => (0.0, c, x),
+        3 => (0.0, x, c),
+        4 => (x, 0.0, c),
+        _ => (c, 0.0, x),
+    };
+    let m = v - c;
+    let to_u8 = |channel: f32| ((channel + m) * 255.0).round() as u8;
+    (to_u8(r), to_u8(g), to_u8(b))
+}
+
+impl From<[f32; 6]> for JointAngles {
+    fn from([j1, j2, j3, j4, j5, j6]: [f32; 6]) -> Self {
+        JointAngles { j1, j2, j3, j4, j5, j6 }
+    }
+}
+
+impl From<JointAngles> for [f32; 6] {
+    fn from(a: JointAngles) -> Self {
+        [a.j1, a.j2, a.j3, a.j4, a.j5, a.j6]
+    }
+}
+
+impl From<[f32; 6]> for Coords {
+    fn from([x, y, z, rx, ry, rz]: [f32; 6]) -> Self {
+        Coords { x, y, z, rx, ry, rz }
+    }
+}
+
+impl From<Coords> for [f32; 6] {
+    fn from(c: Coords) -> Self {
+        [c.x, c.y, c.z, c.rx, c.ry, c.rz]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_servo_status_flags() {
+        let status = ServoStatus::from_bits(ServoStatus::OVERHEAT | ServoStatus::OVERLOAD);
+        assert!(status.overheat());
+        assert!(status.overload());
+        assert!(!status.voltage_error());
+        assert!(!status.sensor_error());
+        assert!(!status.overcurrent());
+        assert!(!status.angle_error());
+        assert!(!status.is_ok());
+        assert!(ServoStatus::default().is_ok());
+    }
+
+    #[test]
+    fn test_angle_unit() {
+        assert_eq!(AngleUnit::Degrees.to_degrees(90.0), 90.0);
+        assert_eq!(AngleUnit::Degrees.convert_from_degrees(90.0), 90.0);
+        assert!((AngleUnit::Radians.to_degrees(core::f32::consts::FRAC_PI_2) - 90.0).abs() < 1e-4);
+        assert!((AngleUnit::Radians.convert_from_degrees(180.0) - core::f32::consts::PI).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_joint_angles_conversion() {
+        let angles = JointAngles::from([1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+        assert_eq!(angles.j1, 1.0);
+        assert_eq!(angles.j6, 6.0);
+        assert_eq!(<[f32; 6]>::from(angles), [1.0, 2.0, 3.0, 4.0, 5.0, 6.0]);
+    }
+
+    #[test]
+    #[cfg(feature = "std")]
+    fn test_hsv_to_rgb() {
+        assert_eq!(hsv_to_rgb(0.0, 1.0, 1.0), (255, 0, 0));
+        assert_eq!(hsv_to_rgb(120.0, 1.0, 1.0), (0, 255, 0));
+        assert_eq!(hsv_to_rgb(240.0, 1.0, 1.0), (0, 0, 255));
+        assert_eq!(hsv_to_rgb(60.0, 1.0, 1.0), Color::Yellow.rgb());
+        assert_eq!(hsv_to_rgb(300.0, 1.0, 1.0), Color::Magenta.rgb());
+        assert_eq!(hsv_to_rgb(-60.0, 1.0, 1.0), Color::Magenta.rgb());
+        assert_eq!(hsv_to_rgb(360.0, 1.0, 1.0), Color::Red.rgb());
+        assert_eq!(hsv_to_rgb(30.0, 1.0, 0.5), (128, 64, 0));
+        assert_eq!(hsv_to_rgb(200.0, 0.0, 1.0), Color::White.rgb());
+        assert_eq!(hsv_to_rgb(200.0, 1.0, 0.0), Color::Off.rgb());
+    }
+
+    #[test]
+    fn test_axis() {
+        assert_eq!(Axis::X as u8, 1);
+        assert_eq!(Axis::Rz.index(), 5);
+        assert!(!Axis::Z.is_rotation());
+        assert!(Axis::Rx.is_rotation());
+    }
+
+    #[test]
+    fn test_coords_conversion() {
+        let mut pose = Coords::from([10.0, 20.0, 30.0, 40.0, 50.0, 60.0]);
+        assert_eq!((pose.x, pose.y, pose.z), (10.0, 20.0, 30.0));
+        assert_eq!((pose.rx, pose.ry, pose.rz), (40.0, 50.0, 60.0));
+
+        pose.z += 10.0;
+        assert_eq!(<[f32; 6]>::from(pose), [10.0, 20.0, 40.0, 40.0, 50.0, 60.0]);
+    }
+}
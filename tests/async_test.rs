@@ -0,0 +1,106 @@
+/*
+ * Copyright (C) 2026 Progress Robotics UG
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+#![cfg(feature = "tokio")]
+
+use mycobot_rs::{AsyncMyCobot, ClampMode, Error, MockSerial, MoveMode};
+use std::time::Duration;
+use tokio::io::AsyncWriteExt;
+
+#[tokio::test]
+async fn test_async_get_angles() {
+    let mut mock = MockSerial::new();
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x20];
+    // Joint 1 = 0x0064 -> 1.00 degree
+    response.extend_from_slice(&[0x00, 0x64]);
+    response.extend_from_slice(&[0; 10]);
+    response.push(0xFA);
+    mock.push_read(&response);
+
+    let mut robot = AsyncMyCobot::new(mock);
+    let angles = robot.get_angles().await.unwrap();
+    assert_eq!(<[f32; 6]>::from(angles), [1.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+
+    let written = robot.into_inner().pop_write();
+    assert_eq!(written, vec![0xFE, 0xFE, 0x02, 0x20, 0xFA]);
+}
+
+#[tokio::test]
+async fn test_async_write_angles() {
+    let mut robot = AsyncMyCobot::new(MockSerial::new());
+    robot.write_angles([1.0, 0.0, 0.0, 0.0, 0.0, 0.0], 50).await.unwrap();
+
+    let written = robot.port.pop_write();
+    assert_eq!(&written[..6], &[0xFE, 0xFE, 0x0F, 0x22, 0x00, 0x64]);
+    assert_eq!(&written[16..], &[50, 0xFA]);
+}
+
+#[tokio::test]
+async fn test_async_timeout() {
+    let mut robot = AsyncMyCobot::new(MockSerial::new());
+    robot.set_timeout(Duration::from_millis(10));
+    assert!(matches!(robot.is_powered_on().await, Err(Error::Timeout)));
+}
+
+#[tokio::test]
+async fn test_async_buffers_following_packets() {
+    let mut mock = MockSerial::new();
+    // Both answers arrive in one read, the second one is kept for the next request
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA, 0xFE, 0xFE, 0x03, 0x12, 0x00, 0xFA]);
+
+    let mut robot = AsyncMyCobot::new(mock);
+    robot.set_timeout(Duration::from_millis(10));
+    assert!(robot.is_powered_on().await.unwrap());
+    assert!(!robot.is_powered_on().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_async_closed_port() {
+    let (client, mut server) = tokio::io::duplex(64);
+    server.shutdown().await.unwrap();
+
+    let mut robot = AsyncMyCobot::new(client);
+    let result = robot.is_powered_on().await;
+    assert!(matches!(result, Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof));
+}
+
+#[tokio::test]
+async fn test_async_resyncs_on_header() {
+    let mut mock = MockSerial::new();
+    // Noise and a truncated frame before the answer
+    mock.push_read(&[0x00, 0x12, 0xFE, 0xFE, 0x03, 0x12, 0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+
+    let mut robot = AsyncMyCobot::new(mock);
+    robot.set_timeout(Duration::from_millis(10));
+    assert!(robot.is_powered_on().await.unwrap());
+}
+
+#[tokio::test]
+async fn test_async_rejects_out_of_range_targets() {
+    let mut robot = AsyncMyCobot::new(MockSerial::new());
+    let result = robot.write_angles([0.0, 200.0, 0.0, 0.0, 0.0, 0.0], 50).await;
+    assert!(matches!(result, Err(Error::InvalidParameter(msg)) if msg.contains("joint 2")));
+    let result = robot.write_coords([0.0, 0.0, 900.0, 0.0, 0.0, 0.0], 50, MoveMode::Angular).await;
+    assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+
+    // Clamped to the limit instead
+    robot.set_clamp_mode(ClampMode::Clamp);
+    robot.set_angle_limits([(-90.0, 90.0); 6]);
+    robot.write_angles([0.0, 200.0, 0.0, 0.0, 0.0, 0.0], 50).await.unwrap();
+    assert_eq!(&robot.port.pop_write()[6..8], &[0x23, 0x28]);
+}
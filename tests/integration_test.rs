@@ -15,7 +15,14 @@
  * along with this program.  If not, see <https://www.gnu.org/licenses/>.
  */
 
-use mycobot_rs::{MockSerial, MyCobot};
+use mycobot_rs::{
+    pin_state, AngleUnit, Axis, ClampMode, Clock, Color, Command, Coords, Direction, Error,
+    FakeClock, FaultyPort, GripperType, JointAngles, MockSerial, MoveMode, MyCobot, MyCobotBuilder,
+    PinMode, RecordingPort, ReplayPort, RetryPolicy, RobotError, RobotModel, RobotStatus,
+    SerialPort, ServoStatus, TcpSerial,
+};
+use mycobot_rs::protocol::Packet;
+use std::time::Duration;
 
 #[test]
 fn test_get_angles() {
@@ -29,9 +36,7 @@ fn test_get_angles() {
     // Angles: 0.0 for all.
     // 0.0 * 100 = 0 -> 0x0000
     let mut response = vec![0xFE, 0xFE, 0x0E, 0x20];
-    for _ in 0..12 {
-        response.push(0);
-    }
+    response.extend_from_slice(&[0; 12]);
     response.push(0xFA);
 
     mock.push_read(&response);
@@ -40,7 +45,7 @@ fn test_get_angles() {
     let angles = robot.get_angles().unwrap();
 
     // Use approx comparison if needed, but 0.0 is exact
-    assert_eq!(angles, [0.0; 6]);
+    assert_eq!(angles, JointAngles::default());
 
     // Verify request
     // We need to access the mock from the robot.
@@ -57,24 +62,2250 @@ fn test_get_angles() {
     assert_eq!(written, vec![0xFE, 0xFE, 0x02, 0x20, 0xFA]);
 }
 
+#[test]
+fn test_get_angles_timed() {
+    let clock = FakeClock::new();
+    let mut mock = MockSerial::new();
+    // joint 1 at 1.0 degree, then at 2.0
+    mock.push_read(&[0xFE, 0xFE, 0x0E, 0x20, 0x00, 0x64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x0E, 0x20, 0x00, 0xC8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x0E, 0x23, 0x03, 0xE8, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+    robot.set_clock(clock.clone());
+
+    let (first, first_at) = robot.get_angles_timed().unwrap();
+    assert_eq!(first[0], 1.0);
+    assert_eq!(first_at, clock.now());
+
+    clock.advance(Duration::from_millis(20));
+    let (second, second_at) = robot.get_angles_timed().unwrap();
+    assert_eq!(second[0], 2.0);
+    assert!(second_at - first_at >= Duration::from_millis(20));
+
+    let (coords, coords_at) = robot.get_coords_timed().unwrap();
+    assert_eq!(coords[0], 100.0);
+    assert!(coords_at >= second_at);
+}
+
 #[test]
 fn test_write_coords() {
     let mock = MockSerial::new();
     let mut robot = MyCobot::new(mock);
 
     let coords = [10.0, 20.0, 30.0, 0.0, 0.0, 0.0];
-    robot.write_coords(coords, 50, 2).unwrap();
+    robot.write_coords(coords, 50, MoveMode::Angular).unwrap();
+
+    let packet = Packet::from_bytes(&robot.port.pop_write()).unwrap();
+    assert_eq!(packet.command, Command::WriteCoords);
+    assert_eq!(
+        packet.payload,
+        // 100, 200, 300 (x10 mm), zero rotations, speed, angular mode
+        [0x00, 0x64, 0x00, 0xC8, 0x01, 0x2C, 0, 0, 0, 0, 0, 0, 50, 0x00]
+    );
+}
+
+#[test]
+fn test_write_slices() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    let angles = vec![1.0, 0.0, 0.0, -1.0, 0.0, 0.0];
+    robot.write_angles_slice(&angles, 50).unwrap();
+    robot.write_angles([1.0, 0.0, 0.0, -1.0, 0.0, 0.0], 50).unwrap();
+    let written = robot.port.pop_write();
+    assert_eq!(written[..written.len() / 2], written[written.len() / 2..]);
+
+    robot.write_coords_slice(&[150.0, 0.0, 200.0, 0.0, 0.0, 0.0], 50, MoveMode::Linear).unwrap();
+    let packet = Packet::from_bytes(&robot.port.pop_write()).unwrap();
+    assert_eq!(packet.command, Command::WriteCoords);
+
+    // A 280 has six joints
+    assert!(matches!(robot.write_angles_slice(&[0.0; 4], 50), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.write_coords_slice(&[0.0; 7], 50, MoveMode::Linear), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+
+    let mut robot = MyCobot::with_model(MockSerial::new(), RobotModel::MyPalletizer260);
+    robot.write_angles_slice(&[1.0, 0.0, 0.0, -1.0], 50).unwrap();
+    assert_eq!(
+        robot.port.pop_write(),
+        vec![0xFE, 0xFE, 0x0B, 0x22, 0x00, 0x64, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x9C, 50, 0xFA]
+    );
+    assert!(matches!(robot.write_angles_slice(&angles, 50), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn test_write_coords_linear() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    robot.write_coords([10.0, 20.0, 30.0, 0.0, 0.0, 0.0], 50, MoveMode::Linear).unwrap();
 
     let written = robot.port.pop_write();
+    assert_eq!(&written[16..], &[50, 0x01, 0xFA]);
+}
+
+#[test]
+fn test_request_skips_unrelated_packet() {
+    let mut mock = MockSerial::new();
+
+    // Unsolicited IsPoweredOn response arrives before the GetAngles response
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x20];
+    // Joint 1 = 0x0064 -> 1.00 degree
+    response.extend_from_slice(&[0x00, 0x64]);
+    response.extend_from_slice(&[0; 10]);
+    response.push(0xFA);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    let angles = robot.get_angles().unwrap();
+
+    assert_eq!(angles.j1, 1.0);
+    assert_eq!(<[f32; 6]>::from(angles), [1.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+}
+
+#[test]
+fn test_timeout() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    assert_eq!(robot.timeout(), Duration::from_millis(500));
+
+    robot.set_timeout(Duration::from_millis(10));
+    assert!(matches!(robot.get_angles(), Err(Error::Timeout)));
+
+    // Per-call override
+    assert!(matches!(robot.get_coords_with_timeout(Duration::from_millis(5)), Err(Error::Timeout)));
+}
+
+#[test]
+fn test_jog_angle() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.jog_angle(3, 1, 40).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x05, 0x30, 0x03, 0x01, 0x28, 0xFA]);
+
+    robot.jog_angle(1, -1, 40).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x05, 0x30, 0x01, 0x00, 0x28, 0xFA]);
+
+    assert!(matches!(robot.jog_angle(0, 1, 40), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.jog_angle(7, 1, 40), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.jog_angle(1, 0, 40), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_jog_coord() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.jog_coord(2, -1, 10).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x05, 0x32, 0x02, 0x00, 0x0A, 0xFA]);
+
+    assert!(matches!(robot.jog_coord(7, 1, 10), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn test_jog_stop() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.jog_stop().unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x34, 0xFA]);
+}
+
+#[test]
+fn test_write_angle() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    // -45.5 * 100 = -4550 -> 0xEE3A
+    robot.write_angle(3, -45.5, 50).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x06, 0x21, 0x03, 0xEE, 0x3A, 0x32, 0xFA]);
+
+    assert!(matches!(robot.write_angle(0, 0.0, 50), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.write_angle(7, 0.0, 50), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn test_get_angle() {
+    let mut mock = MockSerial::new();
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x20];
+    response.extend_from_slice(&[0; 4]);
+    // Joint 3 = 0x1194 -> 45.00 degrees
+    response.extend_from_slice(&[0x11, 0x94]);
+    response.extend_from_slice(&[0; 6]);
+    response.push(0xFA);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_angle(3).unwrap(), 45.0);
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x20, 0xFA]);
+
+    assert!(matches!(robot.get_angle(7), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn test_set_encoder() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.set_encoder(2, 2048).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x05, 0x3A, 0x02, 0x08, 0x00, 0xFA]);
+
+    assert!(matches!(robot.set_encoder(2, 16384), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.set_encoder(0, 2048), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn test_get_encoder() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x04, 0x3B, 0x3F, 0xFF, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_encoder(4).unwrap(), 16383);
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x3B, 0x04, 0xFA]);
+}
 
-    // Verify written data
-    // Header(2) + Length(1) + Cmd(1) + Payload(12+1+1=14) + Footer(1)
-    // Total Length field = 1 + 14 + 1 = 16 (0x10)
+#[test]
+fn test_set_encoders() {
+    let mut robot = MyCobot::new(MockSerial::new());
 
-    assert_eq!(written[0], 0xFE);
-    assert_eq!(written[1], 0xFE);
-    assert_eq!(written[2], 0x10);
-    assert_eq!(written[3], 0x25); // WriteCoords
-    // ... we could verify payload details if needed
+    robot.set_encoders([2048, 1, 0x0102, 0, 16383, 100], 30).unwrap();
+    let written = robot.port.pop_write();
+    assert_eq!(&written[..4], &[0xFE, 0xFE, 0x0F, 0x3C]);
+    assert_eq!(
+        &written[4..17],
+        &[0x08, 0x00, 0x00, 0x01, 0x01, 0x02, 0x00, 0x00, 0x3F, 0xFF, 0x00, 0x64, 30]
+    );
     assert_eq!(written.last(), Some(&0xFA));
+
+    assert!(matches!(robot.set_encoders([0, 0, 0, 0, 0, 20000], 30), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_get_encoders() {
+    let mut mock = MockSerial::new();
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x3D];
+    response.extend_from_slice(&[0x08, 0x00, 0x00, 0x01, 0x01, 0x02, 0x00, 0x00, 0x3F, 0xFF, 0x00, 0x64]);
+    response.push(0xFA);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_encoders().unwrap(), [2048, 1, 0x0102, 0, 16383, 100]);
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x3D, 0xFA]);
+}
+
+#[test]
+fn test_set_speed() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.set_speed(75).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x41, 0x4B, 0xFA]);
+
+    assert!(matches!(robot.set_speed(101), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_get_speed() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_speed().unwrap(), 50);
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x40, 0xFA]);
+}
+
+#[test]
+fn test_release_all_servos() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.release_all_servos().unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x13, 0xFA]);
+}
+
+#[test]
+fn test_is_controller_connected() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x14, 0x01, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x14, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert!(robot.is_controller_connected().unwrap());
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x14, 0xFA]);
+    assert!(!robot.is_controller_connected().unwrap());
+}
+
+#[test]
+fn test_set_gripper_value() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.set_gripper_value(60, 20).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x04, 0x67, 0x3C, 0x14, 0xFA]);
+
+    assert!(matches!(robot.set_gripper_value(101, 20), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.set_gripper_value(60, 101), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_get_gripper_value() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x65, 0x2A, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_gripper_value().unwrap(), 42);
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x65, 0xFA]);
+}
+
+#[test]
+fn test_set_gripper_state() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.set_gripper_state(true, 70).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x04, 0x66, 0x00, 0x46, 0xFA]);
+
+    robot.set_gripper_state(false, 70).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x04, 0x66, 0x01, 0x46, 0xFA]);
+
+    assert!(matches!(robot.set_gripper_state(true, 101), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_gripper_type() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x65, 0x2A, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.gripper_type(), None);
+
+    robot.set_gripper_type(GripperType::Parallel);
+    assert_eq!(robot.gripper_type(), Some(GripperType::Parallel));
+
+    robot.set_gripper_value(60, 20).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x05, 0x67, 0x3C, 0x14, 0x03, 0xFA]);
+
+    robot.set_gripper_type(GripperType::Flexible);
+    robot.set_gripper_state(true, 70).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x05, 0x66, 0x00, 0x46, 0x04, 0xFA]);
+
+    assert_eq!(robot.get_gripper_value().unwrap(), 42);
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x65, 0x04, 0xFA]);
+}
+
+#[test]
+fn test_get_gripper_type() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x6C, 0x03, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x6C, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_gripper_type().unwrap(), Some(GripperType::Parallel));
+    assert_eq!(robot.get_gripper_type().unwrap(), None);
+    assert_eq!(robot.port.pop_write(), [[0xFE, 0xFE, 0x02, 0x6C, 0xFA]; 2].concat());
+
+    // The reported code round-trips through the type sent with gripper commands
+    for gripper in [GripperType::Adaptive, GripperType::Parallel, GripperType::Flexible] {
+        assert_eq!(GripperType::from_code(u8::from(gripper)), Some(gripper));
+    }
+}
+
+#[test]
+fn test_is_gripper_moving() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x69, 0x01, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert!(robot.is_gripper_moving().unwrap());
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x69, 0xFA]);
+}
+
+#[test]
+fn test_set_pin_mode() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.set_pin_mode(23, PinMode::Output).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x04, 0x60, 0x17, 0x01, 0xFA]);
+
+    robot.set_pin_mode(33, PinMode::Input).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x04, 0x60, 0x21, 0x00, 0xFA]);
+}
+
+#[test]
+fn test_set_digital_output() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.set_digital_output(23, true).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x04, 0x61, 0x17, 0x01, 0xFA]);
+}
+
+#[test]
+fn test_get_digital_input() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x62, 0x01, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert!(robot.get_digital_input(33).unwrap());
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x62, 0x21, 0xFA]);
+}
+
+#[test]
+fn test_set_basic_output() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.set_basic_output(5, false).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x04, 0xA0, 0x05, 0x00, 0xFA]);
+}
+
+#[test]
+fn test_get_basic_input() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0xA1, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert!(!robot.get_basic_input(2).unwrap());
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0xA1, 0x02, 0xFA]);
+}
+
+#[test]
+fn test_is_in_position() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2A, 0x01, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2A, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+
+    // Coordinates: X = 10.0mm -> 100 (0x0064), Rx = 1.0 degree -> 100 (0x0064)
+    assert!(robot.is_in_position(&[10.0, 0.0, 0.0, 1.0, 0.0, 0.0], true).unwrap());
+    let written = robot.port.pop_write();
+    assert_eq!(&written[..4], &[0xFE, 0xFE, 0x0F, 0x2A]);
+    assert_eq!(
+        &written[4..17],
+        &[0x00, 0x64, 0, 0, 0, 0, 0x00, 0x64, 0, 0, 0, 0, 0x01]
+    );
+
+    // Angles: 1.0 degree -> 100 (0x0064)
+    assert!(!robot.is_in_position(&[1.0, 0.0, 0.0, 0.0, 0.0, 0.0], false).unwrap());
+    let written = robot.port.pop_write();
+    assert_eq!(
+        &written[4..17],
+        &[0x00, 0x64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0x00]
+    );
+}
+
+#[test]
+fn test_is_moving() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2B, 0x01, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert!(robot.is_moving().unwrap());
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x2B, 0xFA]);
+}
+
+#[test]
+fn test_sync_move() {
+    let mut mock = MockSerial::new();
+    // moving, moving, idle
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2B, 0x01, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2B, 0x01, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2B, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    robot.sync_move(Duration::from_millis(1), Duration::from_secs(1)).unwrap();
+
+    // Three CheckRunning polls
+    let poll = [0xFE, 0xFE, 0x02, 0x2B, 0xFA];
+    assert_eq!(robot.port.pop_write(), poll.repeat(3));
+    robot.port.assert_all_consumed();
+}
+
+#[test]
+fn test_sync_move_timeout() {
+    let mut mock = MockSerial::new();
+    for _ in 0..10 {
+        mock.push_read(&[0xFE, 0xFE, 0x03, 0x2B, 0x01, 0xFA]);
+    }
+
+    let mut robot = MyCobot::new(mock);
+    let result = robot.sync_move(Duration::from_millis(5), Duration::from_millis(10));
+    assert!(matches!(result, Err(Error::Timeout)));
+}
+
+#[test]
+fn test_get_version() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x01, 0x15, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_version().unwrap(), 2.1);
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x01, 0xFA]);
+}
+
+#[test]
+fn test_read_next_error() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x15, 0x03, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x15, 0x20, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x15, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.read_next_error().unwrap(), Some(RobotError::JointLimit(3)));
+    assert_eq!(robot.read_next_error().unwrap(), Some(RobotError::NoSolution));
+    assert_eq!(robot.read_next_error().unwrap(), None);
+
+    let poll = [0xFE, 0xFE, 0x02, 0x15, 0xFA];
+    assert_eq!(robot.port.pop_write(), poll.repeat(3));
+}
+
+#[test]
+fn test_overload_guard() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x15, 0x11, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x15, 0x11, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x15, 0x20, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+
+    // Off by default
+    assert_eq!(robot.read_next_error().unwrap(), Some(RobotError::Collision(0x11)));
+    robot.port.pop_write();
+
+    robot.set_overload_guard(true);
+    let result = robot.read_next_error();
+    assert!(matches!(result, Err(Error::OverloadProtection(RobotError::Collision(0x11)))));
+    let (packets, _) = Packet::parse_all(&robot.port.pop_write());
+    let commands: Vec<Command> = packets.iter().map(|p| p.command).collect();
+    assert_eq!(commands, [Command::ReadNextError, Command::ReleaseAllServos]);
+
+    // Other errors are passed through
+    assert_eq!(robot.read_next_error().unwrap(), Some(RobotError::NoSolution));
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x15, 0xFA]);
+}
+
+#[test]
+fn test_set_free_move_mode() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.set_free_move_mode(true).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x1A, 0x01, 0xFA]);
+
+    robot.set_free_move_mode(false).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x1A, 0x00, 0xFA]);
+}
+
+#[test]
+fn test_is_free_move_mode() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x1B, 0x01, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert!(robot.is_free_move_mode().unwrap());
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x1B, 0xFA]);
+}
+
+#[test]
+fn test_program_control() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.pause().unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x26, 0xFA]);
+
+    robot.resume().unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x28, 0xFA]);
+
+    robot.stop().unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x29, 0xFA]);
+}
+
+#[test]
+fn test_is_paused() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x27, 0x01, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert!(robot.is_paused().unwrap());
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x27, 0xFA]);
+}
+
+#[test]
+fn test_expect_exchange() {
+    let mut mock = MockSerial::new();
+    mock.expect_exchange(&[0xFE, 0xFE, 0x02, 0x40, 0xFA], &[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA]);
+    mock.expect_exchange(&[0xFE, 0xFE, 0x02, 0x12, 0xFA], &[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+
+    // Nothing is readable before the request is written
+    assert!(mock.read_buffer.is_empty());
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_speed().unwrap(), 50);
+    assert!(robot.is_powered_on().unwrap());
+    robot.port.assert_all_consumed();
+}
+
+#[test]
+#[should_panic(expected = "unexpected request")]
+fn test_expect_exchange_mismatch() {
+    let mut mock = MockSerial::new();
+    mock.expect_exchange(&[0xFE, 0xFE, 0x02, 0x40, 0xFA], &[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    let _ = robot.is_powered_on();
+}
+
+#[test]
+fn test_get_angles_chunked() {
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x20];
+    // Joint 6 = 0xFF9C -> -1.00 degree
+    response.extend_from_slice(&[0; 10]);
+    response.extend_from_slice(&[0xFF, 0x9C]);
+    response.push(0xFA);
+
+    let mut mock = MockSerial::new();
+    mock.push_read_chunked(vec![response[..3].to_vec(), response[3..9].to_vec(), response[9..].to_vec()]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_angles_array().unwrap(), [0.0, 0.0, 0.0, 0.0, 0.0, -1.0]);
+}
+
+#[test]
+fn test_read_delay_timeout() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+    mock.set_read_delay(Duration::from_millis(50));
+
+    let mut robot = MyCobot::new(mock);
+    robot.set_timeout(Duration::from_millis(10));
+    assert!(matches!(robot.is_powered_on(), Err(Error::Timeout)));
+}
+
+#[test]
+fn test_builder_with_port() {
+    let robot = MyCobotBuilder::new("/dev/null", 115_200)
+        .timeout(Duration::from_millis(20))
+        .debug(true)
+        .retries(3)
+        .build_with_port(MockSerial::new());
+
+    assert_eq!(robot.timeout(), Duration::from_millis(20));
+    assert!(robot.debug_mode());
+}
+
+/// A port whose first instance fails every write, while re-opened instances work.
+struct FlakyPort {
+    broken: bool,
+    inner: MockSerial,
+}
+
+static FLAKY_OPENS: std::sync::atomic::AtomicUsize = std::sync::atomic::AtomicUsize::new(0);
+
+impl std::io::Read for FlakyPort {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl std::io::Write for FlakyPort {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        if self.broken {
+            return Err(std::io::Error::new(std::io::ErrorKind::BrokenPipe, "Device unplugged"));
+        }
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SerialPort for FlakyPort {
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn open_device(_path: &std::path::Path, _baud_rate: u32) -> std::io::Result<Self> {
+        let opens = FLAKY_OPENS.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        Ok(FlakyPort { broken: opens == 0, inner: MockSerial::new() })
+    }
+}
+
+#[test]
+fn test_reconnect_policy() {
+    let mut robot: MyCobot<FlakyPort> = MyCobot::open("/dev/flaky", 1_000_000).unwrap();
+    robot.set_reconnect_policy(RetryPolicy::new(2, Duration::from_millis(1)));
+
+    robot.power_on().unwrap();
+    assert_eq!(FLAKY_OPENS.load(std::sync::atomic::Ordering::SeqCst), 2);
+    assert_eq!(robot.port.inner.pop_write(), vec![0xFE, 0xFE, 0x02, 0x10, 0xFA]);
+}
+
+#[test]
+fn test_no_reconnect_without_device() {
+    let mut robot = MyCobot::new(FlakyPort { broken: true, inner: MockSerial::new() });
+    robot.set_reconnect_policy(RetryPolicy::new(2, Duration::from_millis(1)));
+
+    assert!(matches!(robot.power_on(), Err(Error::Io(_))));
+}
+
+#[test]
+fn test_write_angles_newtype() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.write_angles([1.0, 0.0, 0.0, 0.0, 0.0, 0.0], 50).unwrap();
+    let from_array = robot.port.pop_write();
+
+    robot.write_angles(JointAngles { j1: 1.0, ..Default::default() }, 50).unwrap();
+    assert_eq!(robot.port.pop_write(), from_array);
+
+    let coords = Coords { x: 10.0, y: 20.0, z: 30.0, ..Default::default() };
+    robot.write_coords(coords, 50, MoveMode::Angular).unwrap();
+    let from_newtype = robot.port.pop_write();
+    robot.write_coords([10.0, 20.0, 30.0, 0.0, 0.0, 0.0], 50, MoveMode::Angular).unwrap();
+    assert_eq!(robot.port.pop_write(), from_newtype);
+}
+
+#[test]
+fn test_get_coords() {
+    let mut mock = MockSerial::new();
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x23];
+    // X = 1500 -> 150.0mm, Rz = -9000 -> -90.00 degrees
+    response.extend_from_slice(&[0x05, 0xDC]);
+    response.extend_from_slice(&[0; 8]);
+    response.extend_from_slice(&[0xDC, 0xD8]);
+    response.push(0xFA);
+    mock.push_read(&response);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    let pose = robot.get_coords().unwrap();
+    assert_eq!(pose.x, 150.0);
+    assert_eq!(pose.rz, -90.0);
+
+    assert_eq!(robot.get_coords_array().unwrap(), [150.0, 0.0, 0.0, 0.0, 0.0, -90.0]);
+}
+
+#[test]
+fn test_angle_limits() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.write_angles([168.0, -135.0, 0.0, 0.0, 0.0, 180.0], 50).unwrap();
+    robot.write_angle(6, -180.0, 50).unwrap();
+    robot.port.pop_write();
+
+    let err = robot.write_angles([0.0, 0.0, 400.0, 0.0, 0.0, 0.0], 50).unwrap_err();
+    match err {
+        Error::InvalidParameter(msg) => assert!(msg.contains("joint 3"), "{}", msg),
+        e => panic!("unexpected error {:?}", e),
+    }
+    assert!(matches!(robot.write_angle(1, -170.0, 50), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+
+    // Other models can widen the limits
+    robot.set_angle_limits([(-360.0, 360.0); 6]);
+    robot.write_angles([0.0, 0.0, 300.0, 0.0, 0.0, 0.0], 50).unwrap();
+}
+
+#[test]
+fn test_coord_limits() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.write_coords([150.0, -150.0, 400.0, -180.0, 0.0, 180.0], 50, MoveMode::Angular).unwrap();
+
+    let err = robot.write_coords([0.0, 0.0, -100.0, 0.0, 0.0, 0.0], 50, MoveMode::Angular).unwrap_err();
+    match err {
+        Error::InvalidParameter(msg) => assert!(msg.contains("axis 3"), "{}", msg),
+        e => panic!("unexpected error {:?}", e),
+    }
+
+    robot.set_coord_limits([(-1000.0, 1000.0); 6]);
+    robot.write_coords([0.0, 0.0, -100.0, 0.0, 0.0, 0.0], 50, MoveMode::Angular).unwrap();
+}
+
+#[test]
+fn test_scaling_overflow() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    robot.set_angle_limits([(-1000.0, 1000.0); 6]);
+    robot.set_coord_limits([(-5000.0, 5000.0); 6]);
+
+    // 327.67 degrees is the largest angle that fits into an i16
+    robot.write_angle(1, 327.67, 50).unwrap();
+    assert_eq!(&robot.port.pop_write()[5..7], &[0x7F, 0xFF]);
+    assert!(matches!(robot.write_angle(1, 400.0, 50), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.write_angles([0.0, 0.0, 0.0, 0.0, 0.0, -400.0], 50), Err(Error::InvalidParameter(_))));
+
+    robot.write_coords([3276.7, 0.0, 0.0, 0.0, 0.0, 0.0], 50, MoveMode::Angular).unwrap();
+    assert!(matches!(
+        robot.write_coords([3300.0, 0.0, 0.0, 0.0, 0.0, 0.0], 50, MoveMode::Angular),
+        Err(Error::InvalidParameter(_))
+    ));
+}
+
+#[test]
+fn test_response_length_mismatch() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x04, 0x40, 0x32, 0x00, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x04, 0x3D, 0x00, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    match robot.get_speed() {
+        Err(e @ Error::LengthMismatch { expected: 1, got: 2 }) => {
+            assert_eq!(e.to_string(), "Expected 1 bytes, got 2");
+            assert!(e.is_recoverable());
+        }
+        r => panic!("unexpected result {:?}", r),
+    }
+    assert!(matches!(robot.get_encoders(), Err(Error::LengthMismatch { expected: 12, got: 2 })));
+}
+
+#[test]
+fn test_poll_async_split_telemetry() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    assert!(robot.poll_async().unwrap().is_empty());
+
+    // First half of an angles frame
+    let mut frame = vec![0xFE, 0xFE, 0x0E, 0x20];
+    frame.extend_from_slice(&[0x00, 0x64]);
+    frame.extend_from_slice(&[0; 10]);
+    frame.push(0xFA);
+    robot.port.push_read(&frame[..7]);
+    assert!(robot.poll_async().unwrap().is_empty());
+
+    // Rest of the frame followed by a complete coords frame
+    robot.port.push_read(&frame[7..]);
+    let mut coords = vec![0xFE, 0xFE, 0x0E, 0x23];
+    coords.extend_from_slice(&[0; 12]);
+    coords.push(0xFA);
+    robot.port.push_read(&coords);
+
+    let packets = robot.poll_async().unwrap();
+    assert_eq!(packets.len(), 2);
+    assert_eq!(packets[0].command, Command::GetAngles);
+    assert_eq!(&packets[0].payload[..2], &[0x00, 0x64]);
+    assert_eq!(packets[1].command, Command::GetCoords);
+
+    // Nothing was sent
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_poll_async_returns_unsolicited_packets() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2B, 0x01, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_speed().unwrap(), 50);
+
+    let packets = robot.poll_async().unwrap();
+    assert_eq!(packets.len(), 1);
+    assert_eq!(packets[0].command, Command::CheckRunning);
+    assert!(robot.poll_async().unwrap().is_empty());
+}
+
+#[test]
+fn test_request_resyncs_after_garbage() {
+    // Deterministic pseudo-random noise, including stray sync bytes
+    let mut seed: u32 = 0x1234_5678;
+    let mut garbage: Vec<u8> = (0..200)
+        .map(|_| {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            (seed >> 16) as u8
+        })
+        .collect();
+    garbage.extend_from_slice(&[0xFE, 0x00, 0xFE]);
+
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x20];
+    response.extend_from_slice(&[0x00, 0x64]);
+    response.extend_from_slice(&[0; 10]);
+    response.push(0xFA);
+
+    let mut mock = MockSerial::new();
+    mock.push_read(&garbage);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_angles().unwrap().j1, 1.0);
+}
+
+#[test]
+fn test_palletizer_angles() {
+    let mut mock = MockSerial::new();
+    // 4 joints only: FE FE 0A 20 [8 bytes] FA
+    mock.push_read(&[0xFE, 0xFE, 0x0A, 0x20, 0x00, 0x64, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x9C, 0xFA]);
+
+    let mut robot = MyCobot::with_model(mock, RobotModel::MyPalletizer260);
+    assert_eq!(robot.get_angles_array().unwrap(), [1.0, 0.0, 0.0, -1.0, 0.0, 0.0]);
+    robot.port.pop_write();
+
+    robot.write_angles([1.0, 0.0, 0.0, -1.0, 0.0, 0.0], 50).unwrap();
+    assert_eq!(
+        robot.port.pop_write(),
+        vec![0xFE, 0xFE, 0x0B, 0x22, 0x00, 0x64, 0x00, 0x00, 0x00, 0x00, 0xFF, 0x9C, 50, 0xFA]
+    );
+
+    // Joints 5 and 6 don't exist
+    assert!(robot.write_angles([0.0, 0.0, 0.0, 0.0, 10.0, 0.0], 50).is_err());
+    assert!(matches!(robot.write_angle(5, 0.0, 50), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn test_model_limits() {
+    let mut robot = MyCobotBuilder::new("/dev/null", 115_200)
+        .model(RobotModel::MyCobotPro320)
+        .build_with_port(MockSerial::new());
+    assert_eq!(robot.model(), RobotModel::MyCobotPro320);
+    // Beyond the 280's 281.45 mm reach, within the Pro 320's
+    robot.write_coords([300.0, 0.0, 200.0, 0.0, 0.0, 0.0], 50, MoveMode::Angular).unwrap();
+
+    robot.set_model(RobotModel::MechArm270);
+    assert!(robot.write_coords([300.0, 0.0, 200.0, 0.0, 0.0, 0.0], 50, MoveMode::Angular).is_err());
+}
+
+#[test]
+fn test_send_unknown_opcode() {
+    let mut mock = MockSerial::new();
+    mock.expect_exchange(&[0xFE, 0xFE, 0x04, 0x7F, 0x01, 0x02, 0xFA], &[0xFE, 0xFE, 0x03, 0x7F, 0x05, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    let response = robot.send_recv(Command::Unknown(0x7F), vec![0x01, 0x02], Duration::from_millis(100)).unwrap();
+    assert_eq!(response, vec![0x05]);
+    robot.port.pop_write();
+
+    robot.send(Command::Unknown(0xEE), vec![]).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0xEE, 0xFA]);
+}
+
+#[test]
+fn test_send_undefined_rejected() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    assert!(matches!(robot.send(Command::Undefined, vec![]), Err(Error::InvalidParameter(_))));
+    let result = robot.send_recv(Command::Undefined, vec![], Duration::from_millis(10));
+    assert!(matches!(result, Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_tool_reference() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x0E, 0x83, 0, 0, 0, 0, 0x01, 0xF9, 0, 0, 0, 0, 0x23, 0x28, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+
+    robot.set_tool_reference([0.0, 0.0, 50.5, 0.0, 0.0, 90.0]).unwrap();
+    assert_eq!(
+        robot.port.pop_write(),
+        vec![0xFE, 0xFE, 0x0E, 0x81, 0, 0, 0, 0, 0x01, 0xF9, 0, 0, 0, 0, 0x23, 0x28, 0xFA]
+    );
+
+    let offset = robot.get_tool_reference().unwrap();
+    assert_eq!(offset, Coords::from([0.0, 0.0, 50.5, 0.0, 0.0, 90.0]));
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x83, 0xFA]);
+}
+
+#[test]
+fn test_world_reference() {
+    let mut mock = MockSerial::new();
+    // x = -200.0, y = 100.0
+    mock.push_read(&[0xFE, 0xFE, 0x0E, 0x84, 0xF8, 0x30, 0x03, 0xE8, 0, 0, 0, 0, 0, 0, 0, 0, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+
+    robot.set_world_reference([-200.0, 100.0, 0.0, 0.0, 0.0, 0.0]).unwrap();
+    // SET_WORLD_REFERENCE (0x82) with the origin as coordinates
+    assert_eq!(
+        robot.port.pop_write(),
+        vec![0xFE, 0xFE, 0x0E, 0x82, 0xF8, 0x30, 0x03, 0xE8, 0, 0, 0, 0, 0, 0, 0, 0, 0xFA]
+    );
+
+    let origin = robot.get_world_reference().unwrap();
+    assert_eq!(origin, Coords::from([-200.0, 100.0, 0.0, 0.0, 0.0, 0.0]));
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x84, 0xFA]);
+}
+
+#[test]
+fn test_write_coord_scaling() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    // X in tenths of a millimeter: 150.5 mm -> 1505 = 0x05E1
+    robot.write_coord(Axis::X, 150.5, 50).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x06, 0x24, 0x01, 0x05, 0xE1, 50, 0xFA]);
+
+    // Rz in hundredths of a degree: -90 deg -> -9000 = 0xDCD8
+    robot.write_coord(Axis::Rz, -90.0, 50).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x06, 0x24, 0x06, 0xDC, 0xD8, 50, 0xFA]);
+
+    assert!(robot.write_coord(Axis::Z, 500.0, 50).is_err());
+}
+
+#[test]
+fn test_get_coord() {
+    let mut mock = MockSerial::new();
+    // X = 1505 -> 150.5 mm, Rx = -9000 -> -90 deg
+    mock.push_read(&[0xFE, 0xFE, 0x04, 0x2D, 0x05, 0xE1, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x04, 0x2D, 0xDC, 0xD8, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_coord(Axis::X).unwrap(), 150.5);
+    assert_eq!(robot.get_coord(Axis::Rx).unwrap(), -90.0);
+
+    // Only the requested axis is queried
+    assert_eq!(
+        robot.port.pop_write(),
+        vec![0xFE, 0xFE, 0x03, 0x2D, 0x01, 0xFA, 0xFE, 0xFE, 0x03, 0x2D, 0x04, 0xFA]
+    );
+}
+
+#[test]
+fn test_controller_error_frame() {
+    let mut mock = MockSerial::new();
+    // The controller answers with an error frame: joint 2 over limit
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x15, 0x02, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    robot.set_timeout(Duration::from_secs(5));
+    let start = std::time::Instant::now();
+    assert!(matches!(robot.get_angles(), Err(Error::Controller(RobotError::JointLimit(2)))));
+    assert!(start.elapsed() < Duration::from_secs(1));
+}
+
+#[test]
+fn test_get_negative_angles() {
+    let mut mock = MockSerial::new();
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x20];
+    // -45.67 -> -4567 = 0xEE29, -1.28 -> -128 = 0xFF80, 1.28 -> 128 = 0x0080
+    response.extend_from_slice(&[0xEE, 0x29, 0xFF, 0x80, 0x00, 0x80, 0xCF, 0xC7, 0x00, 0x00, 0x00, 0x00]);
+    response.push(0xFA);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    let angles = robot.get_angles_array().unwrap();
+    assert_eq!(angles, [-45.67, -1.28, 1.28, -123.45, 0.0, 0.0]);
+}
+
+#[test]
+fn test_get_negative_coords() {
+    let mut mock = MockSerial::new();
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x23];
+    // X = -12.8 mm -> -128 = 0xFF80, Rx = -45.67 deg -> 0xEE29
+    response.extend_from_slice(&[0xFF, 0x80, 0x00, 0x00, 0x00, 0x00, 0xEE, 0x29, 0x00, 0x00, 0x00, 0x00]);
+    response.push(0xFA);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    let coords = robot.get_coords_array().unwrap();
+    assert_eq!(coords, [-12.8, 0.0, 0.0, -45.67, 0.0, 0.0]);
+}
+
+#[test]
+fn test_frame_observer() {
+    let frames = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let captured = frames.clone();
+
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+    robot.set_frame_observer(Box::new(move |direction, frame| {
+        captured.lock().unwrap().push((direction, frame.to_vec()));
+    }));
+
+    assert!(robot.is_powered_on().unwrap());
+    assert_eq!(
+        *frames.lock().unwrap(),
+        vec![
+            (Direction::Tx, vec![0xFE, 0xFE, 0x02, 0x12, 0xFA]),
+            (Direction::Rx, vec![0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]),
+        ]
+    );
+}
+
+#[test]
+fn test_play_trajectory() {
+    let mut mock = MockSerial::new();
+    // Each waypoint is reached on the first CheckRunning poll
+    for _ in 0..2 {
+        mock.push_read(&[0xFE, 0xFE, 0x03, 0x2B, 0x00, 0xFA]);
+        mock.push_read(&[0xFE, 0xFE, 0x03, 0x2A, 0x01, 0xFA]);
+    }
+
+    let mut robot = MyCobot::new(mock);
+    let points = [
+        (JointAngles { j1: 1.0, ..Default::default() }, 50, Duration::from_millis(1)),
+        (JointAngles { j2: -1.0, ..Default::default() }, 80, Duration::ZERO),
+    ];
+    robot.play_trajectory(&points).unwrap();
+
+    let mut expected = vec![0xFE, 0xFE, 0x0F, 0x22, 0x00, 0x64];
+    expected.extend_from_slice(&[0; 10]);
+    expected.extend_from_slice(&[50, 0xFA]);
+    expected.extend_from_slice(&[0xFE, 0xFE, 0x02, 0x2B, 0xFA]);
+    expected.extend_from_slice(&[0xFE, 0xFE, 0x0F, 0x2A, 0x00, 0x64]);
+    expected.extend_from_slice(&[0; 10]);
+    expected.extend_from_slice(&[0x00, 0xFA]);
+    expected.extend_from_slice(&[0xFE, 0xFE, 0x0F, 0x22, 0x00, 0x00, 0xFF, 0x9C]);
+    expected.extend_from_slice(&[0; 8]);
+    expected.extend_from_slice(&[80, 0xFA]);
+    expected.extend_from_slice(&[0xFE, 0xFE, 0x02, 0x2B, 0xFA]);
+    expected.extend_from_slice(&[0xFE, 0xFE, 0x0F, 0x2A, 0x00, 0x00, 0xFF, 0x9C]);
+    expected.extend_from_slice(&[0; 8]);
+    expected.extend_from_slice(&[0x00, 0xFA]);
+    assert_eq!(robot.port.pop_write(), expected);
+}
+
+#[test]
+fn test_play_trajectory_aborts_when_stopped() {
+    let mut mock = MockSerial::new();
+    // The first motion ends without reaching its target, e.g. after a TaskStop
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2B, 0x00, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2A, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    let point = (JointAngles::default(), 50, Duration::ZERO);
+    assert!(matches!(robot.play_trajectory(&[point, point]), Err(Error::MotionStopped)));
+
+    // Target, poll and position check, the second target was never written
+    let written = robot.port.pop_write();
+    assert_eq!(written.len(), 18 + 5 + 18);
+    assert_eq!((written[3], written[26]), (0x22, 0x2A));
+}
+
+#[test]
+fn test_play_trajectory_aborts_on_error() {
+    let mut mock = MockSerial::new();
+    // Collision reported while waiting for the first waypoint
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x15, 0x10, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    let point = (JointAngles::default(), 50, Duration::ZERO);
+    let result = robot.play_trajectory(&[point, point]);
+    assert!(matches!(result, Err(Error::Controller(RobotError::Collision(0x10)))));
+
+    // Only the first target and one poll were sent
+    let written = robot.port.pop_write();
+    assert_eq!(written.iter().filter(|&&b| b == 0x22).count(), 1);
+    assert!(written.ends_with(&[0xFE, 0xFE, 0x02, 0x2B, 0xFA]));
+}
+
+#[test]
+fn test_set_led() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.set_led(Color::Cyan).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x05, 0x6A, 0x00, 0xFF, 0xFF, 0xFA]);
+
+    robot.set_led_hsv(120.0, 1.0, 0.5).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x05, 0x6A, 0x00, 0x80, 0x00, 0xFA]);
+
+    assert!(matches!(robot.set_led_hsv(0.0, 1.5, 1.0), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_write_angles_rejects_speed() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    robot.write_angles([0.0; 6], 100).unwrap();
+    robot.port.pop_write();
+    assert!(matches!(robot.write_angles([0.0; 6], 101), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_write_angle_rejects_speed() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    assert!(matches!(robot.write_angle(1, 0.0, 101), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_write_coords_rejects_speed() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    let coords = [150.0, 0.0, 200.0, 0.0, 0.0, 0.0];
+    assert!(matches!(robot.write_coords(coords, 101, MoveMode::Linear), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_write_coord_rejects_speed() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    assert!(matches!(robot.write_coord(Axis::X, 0.0, 101), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_jog_rejects_speed() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    assert!(matches!(robot.jog_angle(1, 1, 101), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.jog_coord(1, 1, 101), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_last_response() {
+    let mut mock = MockSerial::new();
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x20, 0x00, 0x64];
+    response.extend_from_slice(&[0; 10]);
+    response.push(0xFA);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    assert!(robot.last_response().is_none());
+
+    robot.get_angles().unwrap();
+    let packet = robot.last_response().unwrap();
+    assert_eq!(packet.command, Command::GetAngles);
+    assert_eq!(packet.payload, response[4..16]);
+}
+
+#[test]
+fn test_power_on_blocking() {
+    let mut mock = MockSerial::new();
+    // not powered, not powered, powered
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x00, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x00, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    robot.power_on_blocking(Duration::from_secs(1)).unwrap();
+
+    let mut expected = vec![0xFE, 0xFE, 0x02, 0x10, 0xFA];
+    expected.extend_from_slice(&[0xFE, 0xFE, 0x02, 0x12, 0xFA].repeat(3));
+    assert_eq!(robot.port.pop_write(), expected);
+}
+
+#[test]
+fn test_power_on_blocking_timeout() {
+    let mut mock = MockSerial::new();
+    for _ in 0..4 {
+        mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x00, 0xFA]);
+    }
+
+    let mut robot = MyCobot::new(mock);
+    assert!(matches!(robot.power_on_blocking(Duration::from_millis(60)), Err(Error::Timeout)));
+}
+
+#[test]
+fn test_read_retry_after_truncated_response() {
+    let mut mock = MockSerial::new();
+    // Truncated: only 10 of 12 payload bytes
+    let mut truncated = vec![0xFE, 0xFE, 0x0C, 0x20];
+    truncated.extend_from_slice(&[0; 10]);
+    truncated.push(0xFA);
+    mock.push_read(&truncated);
+    let mut valid = vec![0xFE, 0xFE, 0x0E, 0x20, 0x00, 0x64];
+    valid.extend_from_slice(&[0; 10]);
+    valid.push(0xFA);
+    mock.push_read(&valid);
+
+    let mut robot = MyCobotBuilder::new("/dev/null", 115_200).read_retries(1).build_with_port(mock);
+    assert_eq!(robot.get_angles_array().unwrap(), [1.0, 0.0, 0.0, 0.0, 0.0, 0.0]);
+
+    // The request was sent twice
+    assert_eq!(robot.port.pop_write(), [0xFE, 0xFE, 0x02, 0x20, 0xFA].repeat(2));
+}
+
+#[test]
+fn test_no_read_retry_by_default() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x04, 0x20, 0x00, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert!(matches!(robot.get_angles(), Err(Error::LengthMismatch { .. })));
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x20, 0xFA]);
+}
+
+#[test]
+fn test_max_speed_clamps() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    robot.set_max_speed(40).unwrap();
+
+    robot.write_angles([0.0; 6], 80).unwrap();
+    assert_eq!(&robot.port.pop_write()[16..], &[40, 0xFA]);
+
+    robot.write_coords([150.0, 0.0, 200.0, 0.0, 0.0, 0.0], 20, MoveMode::Angular).unwrap();
+    assert_eq!(&robot.port.pop_write()[16..], &[20, 0x00, 0xFA]);
+
+    assert!(matches!(robot.set_max_speed(101), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn test_joint_max_speed_clamps() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    robot.set_joint_max_speed(1, 10).unwrap();
+
+    // Only moves of joint 1 are slowed down
+    robot.write_angle(1, 0.0, 50).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x06, 0x21, 0x01, 0x00, 0x00, 10, 0xFA]);
+    robot.write_angle(2, 0.0, 50).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x06, 0x21, 0x02, 0x00, 0x00, 50, 0xFA]);
+    robot.jog_angle(1, 1, 50).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x05, 0x30, 0x01, 0x01, 10, 0xFA]);
+
+    // A move of all joints uses the lowest cap
+    robot.write_angles([0.0; 6], 50).unwrap();
+    assert_eq!(&robot.port.pop_write()[16..], &[10, 0xFA]);
+
+    assert!(robot.set_joint_max_speed(7, 10).is_err());
+    assert!(robot.set_joint_max_speed(1, 101).is_err());
+}
+
+#[test]
+fn test_record_and_replay() {
+    let path = std::env::temp_dir().join(format!("mycobot-rs-session-{}.txt", std::process::id()));
+
+    let mut mock = MockSerial::new();
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x20, 0xEE, 0x29];
+    response.extend_from_slice(&[0; 10]);
+    response.push(0xFA);
+    mock.push_read(&response);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+
+    let mut robot = MyCobot::new(RecordingPort::create(mock, &path).unwrap());
+    let recorded = robot.get_angles().unwrap();
+    assert!(robot.is_powered_on().unwrap());
+    drop(robot);
+
+    let mut robot = MyCobot::new(ReplayPort::open(&path).unwrap());
+    assert_eq!(robot.get_angles().unwrap(), recorded);
+    assert!(robot.is_powered_on().unwrap());
+    assert!(robot.port.is_finished());
+
+    // Requests that weren't recorded fail instead of hanging
+    assert!(matches!(robot.get_coords(), Err(Error::Io(_))));
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_get_status() {
+    let mut mock = MockSerial::new();
+    mock.expect_exchange(&[0xFE, 0xFE, 0x02, 0x12, 0xFA], &[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+    mock.expect_exchange(&[0xFE, 0xFE, 0x02, 0x2B, 0xFA], &[0xFE, 0xFE, 0x03, 0x2B, 0x01, 0xFA]);
+    mock.expect_exchange(&[0xFE, 0xFE, 0x02, 0x27, 0xFA], &[0xFE, 0xFE, 0x03, 0x27, 0x00, 0xFA]);
+    mock.expect_exchange(&[0xFE, 0xFE, 0x02, 0x15, 0xFA], &[0xFE, 0xFE, 0x03, 0x15, 0x20, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(
+        robot.get_status().unwrap(),
+        RobotStatus { powered: true, moving: true, paused: false, pending_error: Some(RobotError::NoSolution) }
+    );
+}
+
+#[test]
+fn test_require_power() {
+    let mut mock = MockSerial::new();
+    mock.expect_exchange(&[0xFE, 0xFE, 0x02, 0x12, 0xFA], &[0xFE, 0xFE, 0x03, 0x12, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    robot.set_require_power(true);
+    assert!(matches!(robot.write_angles([0.0; 6], 50), Err(Error::InvalidParameter(_))));
+    // The cached state is used for the next command
+    assert!(matches!(robot.jog_angle(1, 1, 50), Err(Error::InvalidParameter(_))));
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x12, 0xFA]);
+
+    // Powering on forgets the cached state
+    robot.power_on().unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x10, 0xFA]);
+    robot.port.expect_exchange(&[0xFE, 0xFE, 0x02, 0x12, 0xFA], &[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+    robot.write_angles([0.0; 6], 50).unwrap();
+    robot.write_angle(1, 0.0, 50).unwrap();
+    let written = robot.port.pop_write();
+    // One IsPoweredOn, then both moves
+    assert_eq!(&written[..5], &[0xFE, 0xFE, 0x02, 0x12, 0xFA]);
+    assert_eq!(written.len(), 5 + 18 + 9);
+}
+
+#[test]
+fn test_require_power_disabled() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    robot.write_angles([0.0; 6], 50).unwrap();
+    // No power query, only the move
+    assert_eq!(robot.port.pop_write().len(), 18);
+}
+
+#[test]
+fn test_write_encoders_sync() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2B, 0x00, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    robot.write_encoders_sync([2048, 2048, 2048, 2048, 2048, 16383], 30).unwrap();
+
+    let mut expected = vec![0xFE, 0xFE, 0x0F, 0x3C];
+    expected.extend_from_slice(&[0x08, 0x00].repeat(5));
+    expected.extend_from_slice(&[0x3F, 0xFF, 30, 0xFA]);
+    expected.extend_from_slice(&[0xFE, 0xFE, 0x02, 0x2B, 0xFA]);
+    assert_eq!(robot.port.pop_write(), expected);
+
+    assert!(matches!(robot.write_encoders_sync([16384; 6], 30), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.write_encoders_sync([0; 6], 101), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_interpolate_move() {
+    let mut mock = MockSerial::new();
+    // Start pose: joint 1 at 0, joint 2 at 10 degrees
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x20, 0x00, 0x00, 0x03, 0xE8];
+    response.extend_from_slice(&[0; 8]);
+    response.push(0xFA);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    let target = JointAngles { j1: 10.0, ..Default::default() };
+    robot.interpolate_move(target, 5, Duration::ZERO).unwrap();
+
+    let written = robot.port.pop_write();
+    let waypoints: Vec<&[u8]> = written[5..].chunks(18).collect();
+    assert_eq!(waypoints.len(), 5);
+
+    let joint = |frame: &[u8], i: usize| i16::from_be_bytes([frame[4 + i * 2], frame[5 + i * 2]]);
+    let j1: Vec<i16> = waypoints.iter().map(|frame| joint(frame, 0)).collect();
+    let j2: Vec<i16> = waypoints.iter().map(|frame| joint(frame, 1)).collect();
+    assert_eq!(j1, vec![200, 400, 600, 800, 1000]);
+    assert_eq!(j2, vec![800, 600, 400, 200, 0]);
+    assert!(waypoints.iter().all(|frame| frame[3] == 0x22 && frame[16] == 100));
+}
+
+#[test]
+fn test_extended_length_frames() {
+    let mut mock = MockSerial::new();
+    // 300-byte response with the extended length field
+    let mut response = vec![0xFE, 0xFE, 0x00, 0x01, 0x2E, 0x7F];
+    response.extend_from_slice(&[0xAB; 300]);
+    response.push(0xFA);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    assert!(matches!(robot.send(Command::Unknown(0x7F), vec![0; 300]), Err(Error::InvalidParameter(_))));
+
+    robot.set_extended_length(true);
+    let payload = robot.send_recv(Command::Unknown(0x7F), vec![0; 300], Duration::from_millis(100)).unwrap();
+    assert_eq!(payload, vec![0xAB; 300]);
+    assert_eq!(&robot.port.pop_write()[..6], &[0xFE, 0xFE, 0x00, 0x01, 0x2E, 0x7F]);
+}
+
+#[test]
+fn test_zero_length_noise_resyncs() {
+    let mut mock = MockSerial::new();
+    // Noise that looks like an extended length header, then a valid GetSpeed reply
+    mock.push_read(&[0xFE, 0xFE, 0x00, 0xFF, 0xFF]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    robot.set_timeout(Duration::from_millis(100));
+    assert_eq!(robot.get_speed().unwrap(), 50);
+}
+
+#[test]
+fn test_jog_session_stops_on_drop() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    {
+        let mut jog = robot.jog_session();
+        jog.jog_angle(1, 1, 50).unwrap();
+        jog.jog_coord(3, -1, 20).unwrap();
+    }
+    assert_eq!(
+        robot.port.pop_write(),
+        vec![
+            0xFE, 0xFE, 0x05, 0x30, 0x01, 0x01, 50, 0xFA, // JogAngle
+            0xFE, 0xFE, 0x05, 0x32, 0x03, 0x00, 20, 0xFA, // JogCoord
+            0xFE, 0xFE, 0x02, 0x34, 0xFA, // JogStop on drop
+        ]
+    );
+
+    // Nothing to stop if no jog was started or it was already stopped
+    robot.jog_session();
+    let mut jog = robot.jog_session();
+    jog.jog_angle(2, 1, 50).unwrap();
+    jog.stop().unwrap();
+    drop(jog);
+    assert_eq!(robot.port.pop_write().len(), 8 + 5);
+}
+
+#[test]
+fn test_get_digital_inputs() {
+    let mut mock = MockSerial::new();
+    // Pins 19, 22, 23, 33 read high, low, low, high
+    for (pin, level) in [(19, 1), (22, 0), (23, 0), (33, 1)] {
+        mock.expect_exchange(&[0xFE, 0xFE, 0x03, 0x62, pin, 0xFA], &[0xFE, 0xFE, 0x03, 0x62, level, 0xFA]);
+    }
+
+    let mut robot = MyCobot::new(mock);
+    let mask = robot.get_digital_inputs().unwrap();
+    assert_eq!(mask, 0b1001);
+    robot.port.assert_all_consumed();
+
+    assert_eq!(pin_state(mask, 19), Some(true));
+    assert_eq!(pin_state(mask, 22), Some(false));
+    assert_eq!(pin_state(mask, 23), Some(false));
+    assert_eq!(pin_state(mask, 33), Some(true));
+    assert_eq!(pin_state(mask, 5), None);
+}
+
+/// Records when each write happens.
+#[derive(Default)]
+struct TimedPort {
+    writes: Vec<std::time::Instant>,
+}
+
+impl std::io::Read for TimedPort {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Ok(0)
+    }
+}
+
+impl std::io::Write for TimedPort {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.writes.push(std::time::Instant::now());
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SerialPort for TimedPort {
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn test_min_command_interval() {
+    let interval = Duration::from_millis(20);
+    let mut robot = MyCobot::new(TimedPort::default());
+    robot.set_min_command_interval(interval);
+
+    robot.power_on().unwrap();
+    robot.set_led_color(0, 0, 0).unwrap();
+    robot.jog_stop().unwrap();
+
+    let writes = &robot.port.writes;
+    assert_eq!(writes.len(), 3);
+    for pair in writes.windows(2) {
+        assert!(pair[1] - pair[0] >= interval);
+    }
+}
+
+/// A port that never receives anything and never blocks.
+struct SilentPort;
+
+impl std::io::Read for SilentPort {
+    fn read(&mut self, _buf: &mut [u8]) -> std::io::Result<usize> {
+        Ok(0)
+    }
+}
+
+impl std::io::Write for SilentPort {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SerialPort for SilentPort {
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn read_with_timeout(&mut self, _buf: &mut [u8], _timeout: Duration) -> std::io::Result<usize> {
+        Ok(0)
+    }
+}
+
+#[test]
+fn test_fake_clock_timeout() {
+    let clock = FakeClock::new();
+    let mut robot = MyCobot::new(SilentPort);
+    robot.set_clock(clock.clone());
+    robot.set_timeout(Duration::from_secs(60));
+
+    let started = std::time::Instant::now();
+    assert!(matches!(robot.get_angles(), Err(Error::Timeout)));
+
+    assert!(clock.elapsed() >= Duration::from_secs(60));
+    assert!(started.elapsed() < Duration::from_secs(10));
+}
+
+#[test]
+fn test_write_angles_acked() {
+    let mut mock = MockSerial::new();
+    // The controller echoes the command without payload
+    mock.push_read(&[0xFE, 0xFE, 0x02, 0x22, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+    robot.set_timeout(Duration::from_millis(10));
+
+    robot.write_angles_acked([0.0; 6], 50).unwrap();
+    let packet = Packet::from_bytes(&robot.port.pop_write()).unwrap();
+    assert_eq!(packet.command, Command::WriteAngles);
+
+    // Silently dropped
+    assert!(matches!(robot.write_angles_acked([0.0; 6], 50), Err(Error::Timeout)));
+}
+
+#[test]
+fn test_write_coords_acked() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x02, 0x25, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+
+    robot.write_coords_acked([150.0, 0.0, 200.0, 0.0, 0.0, 0.0], 50, MoveMode::Linear).unwrap();
+    let packet = Packet::from_bytes(&robot.port.pop_write()).unwrap();
+    assert_eq!(packet.command, Command::WriteCoords);
+    assert_eq!(packet.payload.last(), Some(&1));
+}
+
+#[test]
+fn test_led_animations() {
+    let clock = FakeClock::new();
+    let mut robot = MyCobot::new(MockSerial::new());
+    robot.set_clock(clock.clone());
+
+    robot.led_blink(Color::Red, 3, Duration::from_millis(100)).unwrap();
+    let written = robot.port.pop_write();
+    let frames: Vec<&[u8]> = written.chunks(8).collect();
+    assert_eq!(frames.len(), 6);
+    assert_eq!(&frames[0][4..7], &[0xFF, 0x00, 0x00]);
+    assert_eq!(&frames[5][4..7], &[0x00, 0x00, 0x00]);
+    assert_eq!(clock.elapsed(), Duration::from_millis(600));
+
+    robot.led_fade(Color::Off, Color::White, Duration::from_millis(400), 4).unwrap();
+    let written = robot.port.pop_write();
+    let frames: Vec<&[u8]> = written.chunks(8).collect();
+    assert_eq!(frames.len(), 5);
+    assert_eq!(&frames[0][4..7], &[0x00, 0x00, 0x00]);
+    assert_eq!(&frames[2][4..7], &[0x80, 0x80, 0x80]);
+    assert_eq!(&frames[4][4..7], &[0xFF, 0xFF, 0xFF]);
+    assert_eq!(clock.elapsed(), Duration::from_millis(1000));
+
+    robot.led_pulse(Color::Blue, Duration::from_millis(200), 2).unwrap();
+    let written = robot.port.pop_write();
+    let frames: Vec<&[u8]> = written.chunks(8).collect();
+    assert_eq!(frames.len(), 6);
+    assert_eq!(&frames[2][4..7], &[0x00, 0x00, 0xFF]);
+    assert_eq!(&frames[5][4..7], &[0x00, 0x00, 0x00]);
+
+    assert!(matches!(
+        robot.led_fade(Color::Off, Color::Red, Duration::from_millis(100), 0),
+        Err(Error::InvalidParameter(_))
+    ));
+}
+
+#[test]
+fn test_error_is_recoverable() {
+    assert!(Error::Timeout.is_recoverable());
+    assert!(Error::ChecksumMismatch.is_recoverable());
+    assert!(Error::UnexpectedCommand { expected: Command::GetAngles, got: Command::GetCoords }.is_recoverable());
+    assert!(Error::Io(std::io::ErrorKind::TimedOut.into()).is_recoverable());
+
+    assert!(!Error::Io(std::io::ErrorKind::NotFound.into()).is_recoverable());
+    assert!(!Error::InvalidParameter("speed".into()).is_recoverable());
+    assert!(!Error::Controller(RobotError::NoSolution).is_recoverable());
+    assert!(!Error::OverloadProtection(RobotError::Collision(0x10)).is_recoverable());
+}
+
+#[test]
+fn test_home() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    robot.home(40).unwrap();
+
+    let mut expected = vec![0xFE, 0xFE, 0x0F, 0x22];
+    expected.extend_from_slice(&[0x00; 12]);
+    expected.extend_from_slice(&[40, 0xFA]);
+    assert_eq!(robot.port.pop_write(), expected);
+
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x2B, 0x00, 0xFA]);
+    let mut robot = MyCobot::with_model(mock, RobotModel::MyPalletizer260);
+    robot.home_sync(40).unwrap();
+
+    let mut expected = vec![0xFE, 0xFE, 0x0B, 0x22];
+    expected.extend_from_slice(&[0x00; 8]);
+    expected.extend_from_slice(&[40, 0xFA]);
+    expected.extend_from_slice(&[0xFE, 0xFE, 0x02, 0x2B, 0xFA]);
+    assert_eq!(robot.port.pop_write(), expected);
+}
+
+#[test]
+fn test_get_servo_currents() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[
+        0xFE, 0xFE, 0x0E, 0xE2, 0x00, 0x64, 0x01, 0x2C, 0x00, 0x00, 0x00, 0x0A, 0x03, 0xE8, 0xFF, 0xFF, 0xFA,
+    ]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_servo_currents().unwrap(), [100.0, 300.0, 0.0, 10.0, 1000.0, -1.0]);
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0xE2, 0xFA]);
+}
+
+#[test]
+fn test_get_servo_temperatures() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x08, 0xE5, 30, 31, 35, 40, 28, 29, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x04, 0xE5, 30, 31, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    assert_eq!(robot.get_servo_temperatures().unwrap(), [30.0, 31.0, 35.0, 40.0, 28.0, 29.0]);
+    assert!(matches!(robot.get_servo_temperatures(), Err(Error::LengthMismatch { expected: 6, got: 2 })));
+}
+
+#[test]
+fn test_palletizer_diagnostics() {
+    // Four joints answer with four values, the rest stay at 0
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x0A, 0x3D, 0x08, 0x00, 0x00, 0x01, 0x01, 0x02, 0x3F, 0xFF, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x0A, 0xE2, 0x00, 0x64, 0x01, 0x2C, 0x00, 0x00, 0xFF, 0xFF, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x06, 0xE4, 0x00, 0x24, 0x00, 0x01, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x06, 0xE5, 30, 31, 35, 40, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x08, 0xE5, 30, 31, 35, 40, 28, 29, 0xFA]);
+
+    let mut robot = MyCobot::with_model(mock, RobotModel::MyPalletizer260);
+    assert_eq!(robot.get_encoders().unwrap(), [2048, 1, 0x0102, 16383, 0, 0]);
+    assert_eq!(robot.get_servo_currents().unwrap(), [100.0, 300.0, 0.0, -1.0, 0.0, 0.0]);
+
+    let status = robot.get_servo_status().unwrap();
+    assert!(status[1].overheat() && status[3].voltage_error());
+    assert!(status[4].is_ok() && status[5].is_ok());
+
+    assert_eq!(robot.get_servo_temperatures().unwrap(), [30.0, 31.0, 35.0, 40.0, 0.0, 0.0]);
+    // A six-joint reply doesn't fit the model
+    assert!(matches!(robot.get_servo_temperatures(), Err(Error::LengthMismatch { expected: 4, got: 6 })));
+}
+
+#[test]
+fn test_release_and_focus_servo() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    robot.release_servo(3).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x56, 0x03, 0xFA]);
+
+    robot.focus_servo(3).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x57, 0x03, 0xFA]);
+
+    assert!(matches!(robot.release_servo(7), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.focus_servo(0), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_servo_calibration_requires_flag() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    assert!(matches!(robot.set_servo_calibration(2), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+
+    robot.set_allow_calibration(true);
+    robot.set_servo_calibration(2).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x54, 0x02, 0xFA]);
+
+    assert!(matches!(robot.set_servo_calibration(7), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn test_shutdown() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    robot.shutdown().unwrap();
+
+    let mut expected = vec![0xFE, 0xFE, 0x02, 0x29, 0xFA];
+    expected.extend_from_slice(&[0xFE, 0xFE, 0x02, 0x11, 0xFA]);
+    assert_eq!(robot.port.pop_write(), expected);
+}
+
+#[test]
+fn test_angle_unit_radians() {
+    use std::f32::consts::{FRAC_PI_2, FRAC_PI_4, PI};
+
+    let mut mock = MockSerial::new();
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x20, 0x23, 0x28, 0xEE, 0x6C];
+    response.extend_from_slice(&[0; 8]);
+    response.push(0xFA);
+    mock.push_read(&response);
+    // x = 150.0 mm, rx = 180 deg
+    let mut response = vec![0xFE, 0xFE, 0x0E, 0x23, 0x05, 0xDC, 0x00, 0x00, 0x00, 0x00, 0x46, 0x50];
+    response.extend_from_slice(&[0; 4]);
+    response.push(0xFA);
+    mock.push_read(&response);
+
+    let mut robot = MyCobot::new(mock);
+    robot.set_angle_unit(AngleUnit::Radians);
+
+    let angles = robot.get_angles().unwrap();
+    assert!((angles.j1 - FRAC_PI_2).abs() < 1e-4);
+    assert!((angles.j2 + FRAC_PI_4).abs() < 1e-4);
+    let coords = robot.get_coords().unwrap();
+    assert_eq!(coords.x, 150.0);
+    assert!((coords.rx - PI).abs() < 1e-4);
+    robot.port.pop_write();
+
+    robot.write_angles([FRAC_PI_2, 0.0, 0.0, 0.0, 0.0, 0.0], 50).unwrap();
+    assert_eq!(&robot.port.pop_write()[4..6], &[0x23, 0x28]);
+
+    robot.write_coord(Axis::Rx, -FRAC_PI_2, 50).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x06, 0x24, 0x04, 0xDC, 0xD8, 50, 0xFA]);
+
+    robot.write_coord(Axis::X, 100.0, 50).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x06, 0x24, 0x01, 0x03, 0xE8, 50, 0xFA]);
+
+    // Limits are still checked in degrees
+    assert!(matches!(robot.write_angle(1, PI, 50), Err(Error::InvalidParameter(_))));
+}
+
+#[test]
+fn test_fresh_mode() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x17, 0x00, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+
+    robot.set_fresh_mode(true).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x16, 0x01, 0xFA]);
+    robot.set_fresh_mode(false).unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x16, 0x00, 0xFA]);
+
+    assert!(!robot.get_fresh_mode().unwrap());
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x17, 0xFA]);
+}
+
+#[test]
+fn test_get_angles_from_encoders() {
+    let mut mock = MockSerial::new();
+    // 2048, 3072, 1024, 2148, 0, 4000
+    mock.push_read(&[
+        0xFE, 0xFE, 0x0E, 0x3D, 0x08, 0x00, 0x0C, 0x00, 0x04, 0x00, 0x08, 0x64, 0x00, 0x00, 0x0F, 0xA0, 0xFA,
+    ]);
+    mock.push_read(&[
+        0xFE, 0xFE, 0x0E, 0x3D, 0x08, 0x00, 0x0C, 0x00, 0x04, 0x00, 0x08, 0x64, 0x00, 0x00, 0x0F, 0xA0, 0xFA,
+    ]);
+    let mut robot = MyCobot::new(mock);
+
+    assert_eq!(robot.get_angles_from_encoders().unwrap(), [0.0, 90.0, -90.0, 8.789_062_5, -180.0, 171.5625]);
+
+    robot.set_encoder_calibration([
+        (2048.0, 0.1),
+        (2000.0, -0.125),
+        (1024.0, 0.09),
+        (2148.0, 1.0),
+        (100.0, 0.5),
+        (4000.0, 0.01),
+    ]);
+    assert_eq!(robot.get_angles_from_encoders().unwrap(), [0.0, -134.0, 0.0, 0.0, -50.0, 0.0]);
+}
+
+/// Wraps a `MockSerial` and counts the reads made on it.
+struct CountingPort {
+    inner: MockSerial,
+    reads: usize,
+}
+
+impl std::io::Read for CountingPort {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.reads += 1;
+        self.inner.read(buf)
+    }
+}
+
+impl std::io::Write for CountingPort {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        std::io::Write::flush(&mut self.inner)
+    }
+}
+
+impl SerialPort for CountingPort {
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn read_with_timeout(&mut self, buf: &mut [u8], timeout: Duration) -> std::io::Result<usize> {
+        self.reads += 1;
+        self.inner.read_with_timeout(buf, timeout)
+    }
+}
+
+#[test]
+fn test_request_reads_in_chunks() {
+    let mut mock = MockSerial::new();
+    // An unsolicited frame and the responses to two requests in one read
+    let mut data = vec![0xFE, 0xFE, 0x03, 0x2B, 0x01, 0xFA];
+    data.extend_from_slice(&[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA]);
+    data.extend_from_slice(&[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+    mock.push_read(&data);
+
+    let mut robot = MyCobot::new(CountingPort { inner: mock, reads: 0 });
+    assert_eq!(robot.get_speed().unwrap(), 50);
+    assert_eq!(robot.port.reads, 1);
+
+    // Served from the bytes already read
+    assert!(robot.is_powered_on().unwrap());
+    assert_eq!(robot.port.reads, 1);
+    robot.port.inner.assert_all_consumed();
+
+    let feedback = robot.poll_async().unwrap();
+    assert_eq!(feedback.len(), 1);
+    assert_eq!(feedback[0].command, Command::CheckRunning);
+}
+
+#[test]
+fn test_power_state_cache() {
+    let clock = FakeClock::new();
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+    robot.set_clock(clock.clone());
+    robot.set_power_state_ttl(Duration::from_millis(500));
+
+    // Miss
+    assert_eq!(robot.cached_power_state(), None);
+    assert!(robot.is_powered_on_cached().unwrap());
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x12, 0xFA]);
+
+    // Hit
+    clock.advance(Duration::from_millis(400));
+    assert_eq!(robot.cached_power_state(), Some(true));
+    assert!(robot.is_powered_on_cached().unwrap());
+    assert!(robot.port.pop_write().is_empty());
+
+    // Expired
+    clock.advance(Duration::from_millis(100));
+    assert_eq!(robot.cached_power_state(), None);
+    assert!(robot.is_powered_on_cached().unwrap());
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x12, 0xFA]);
+
+    // Invalidated
+    robot.power_off().unwrap();
+    assert_eq!(robot.cached_power_state(), Some(false));
+    robot.power_on().unwrap();
+    assert_eq!(robot.cached_power_state(), None);
+}
+
+#[test]
+fn test_power_off_write_failure() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+    let mut port = FaultyPort::new(mock);
+    // The IsPoweredOn request is the first write, PowerOff the second
+    port.fail_write(2, std::io::ErrorKind::BrokenPipe);
+    let mut robot = MyCobot::new(port);
+    robot.set_power_state_ttl(Duration::from_secs(60));
+
+    assert!(robot.is_powered_on_cached().unwrap());
+    assert!(matches!(robot.power_off(), Err(Error::Io(_))));
+    // The arm never got the command, so it's not cached as off
+    assert_eq!(robot.cached_power_state(), None);
+}
+
+#[test]
+fn test_flush_input() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA, 0xFE, 0xFE, 0x03]);
+    let mut robot = MyCobot::new(mock);
+    // Leaves the start of the next frame buffered
+    assert_eq!(robot.get_speed().unwrap(), 50);
+
+    robot.port.push_read_chunked(vec![vec![0x12], vec![0x01]]);
+    robot.flush_input().unwrap();
+    robot.port.assert_all_consumed();
+
+    // The rest of the frame can't complete the discarded start
+    robot.port.push_read(&[0xFA]);
+    assert!(robot.poll_async().unwrap().is_empty());
+}
+
+#[test]
+fn test_flush_before_request() {
+    let mut mock = MockSerial::new();
+    // Late response to an earlier request that timed out
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x12, 0x00, 0xFA]);
+    mock.expect_exchange(&[0xFE, 0xFE, 0x02, 0x12, 0xFA], &[0xFE, 0xFE, 0x03, 0x12, 0x01, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+    robot.set_flush_before_request(true);
+
+    assert!(robot.is_powered_on().unwrap());
+    robot.port.assert_all_consumed();
+}
+
+#[test]
+fn test_motion_rejects_non_finite_values() {
+    let mut robot = MyCobot::new(MockSerial::new());
+
+    let expect_index = |result: Result<(), Error>, index: usize| match result {
+        Err(Error::InvalidParameter(msg)) => assert_eq!(msg, format!("non-finite value at index {}", index)),
+        r => panic!("unexpected result {:?}", r),
+    };
+
+    expect_index(robot.write_angles([0.0, f32::NAN, 0.0, 0.0, 0.0, 0.0], 50), 1);
+    expect_index(robot.write_angles([0.0, 0.0, 0.0, 0.0, 0.0, f32::INFINITY], 50), 5);
+    expect_index(robot.write_angle(3, f32::NEG_INFINITY, 50), 2);
+    expect_index(robot.write_coords([f32::NAN, 0.0, 200.0, 0.0, 0.0, 0.0], 50, MoveMode::Angular), 0);
+    expect_index(robot.write_coord(Axis::Rz, f32::NAN, 50), 5);
+    expect_index(
+        robot.interpolate_move(JointAngles::from([f32::NAN; 6]), 2, Duration::ZERO),
+        0,
+    );
+
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_tcp_serial() {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut request = [0u8; 5];
+        stream.read_exact(&mut request).unwrap();
+        assert_eq!(request, [0xFE, 0xFE, 0x02, 0x40, 0xFA]);
+        stream.write_all(&[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA]).unwrap();
+
+        // Swallow the next request without answering
+        stream.read_exact(&mut request).unwrap();
+        stream
+    });
+
+    let port = TcpSerial::connect_timeout(&addr, Duration::from_secs(5)).unwrap();
+    let mut robot = MyCobot::new(port);
+    robot.set_timeout(Duration::from_millis(100));
+
+    assert_eq!(robot.get_speed().unwrap(), 50);
+    assert!(matches!(robot.is_powered_on(), Err(Error::Timeout)));
+    assert!(robot.poll_async().unwrap().is_empty());
+
+    drop(server.join().unwrap());
+}
+
+fn assert_sync<T: Sync>() {}
+
+#[test]
+fn test_mycobot_is_sync() {
+    // Clock and frame observer are boxed, they must not take Sync away
+    assert_sync::<MyCobot<MockSerial>>();
+}
+
+#[test]
+fn test_tcp_serial_closed() {
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = std::thread::spawn(move || drop(listener.accept().unwrap()));
+
+    let port = TcpSerial::connect_timeout(&addr, Duration::from_secs(5)).unwrap();
+    server.join().unwrap();
+    let mut robot = MyCobot::new(port);
+    robot.set_timeout(Duration::from_secs(5));
+
+    let result = robot.get_speed();
+    assert!(matches!(result, Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::UnexpectedEof));
+}
+
+#[test]
+fn test_get_servo_status() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x08, 0xE4, 0x00, 0x24, 0x00, 0x01, 0x00, 0x0A, 0xFA]);
+
+    let mut robot = MyCobot::new(mock);
+    let status = robot.get_servo_status().unwrap();
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0xE4, 0xFA]);
+
+    assert!(status[0].is_ok());
+    // Joint 2 overheated and stalled
+    assert!(status[1].overheat() && status[1].overload());
+    assert!(!status[1].overcurrent());
+    assert!(status[3].voltage_error());
+    assert!(status[5].sensor_error() && status[5].overcurrent());
+    assert_eq!(status[5].bits(), ServoStatus::SENSOR | ServoStatus::OVERCURRENT);
+}
+
+#[test]
+fn test_mock_would_block() {
+    let clock = FakeClock::new();
+    let mut mock = MockSerial::new();
+    mock.push_would_block(3);
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+    robot.set_clock(clock.clone());
+    robot.set_timeout(Duration::from_millis(10));
+
+    // The response arrives after three empty polls
+    assert_eq!(robot.get_speed().unwrap(), 50);
+    assert_eq!(clock.elapsed(), Duration::from_millis(3));
+
+    // Still nothing when the deadline passes
+    robot.port.push_would_block(20);
+    robot.port.push_read(&[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA]);
+    assert!(matches!(robot.get_speed(), Err(Error::Timeout)));
+    assert_eq!(robot.port.remaining_reads(), 6);
+
+    let mut buf = [0u8; 8];
+    let err = std::io::Read::read(&mut robot.port, &mut buf).unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+}
+
+#[test]
+fn test_move_linear() {
+    let mut mock = MockSerial::new();
+    // x = 100.0, y = 0.0, z = 200.0, rx = 0, ry = 0, rz = 90
+    mock.push_read(&[
+        0xFE, 0xFE, 0x0E, 0x23, 0x03, 0xE8, 0x00, 0x00, 0x07, 0xD0, 0x00, 0x00, 0x00, 0x00, 0x23, 0x28, 0xFA,
+    ]);
+    let mut robot = MyCobot::new(mock);
+
+    robot.move_linear(Coords::from([130.0, 0.0, 200.0, 0.0, 0.0, 90.0]), 40, 10.0).unwrap();
+
+    let written = robot.port.pop_write();
+    assert_eq!(&written[..5], &[0xFE, 0xFE, 0x02, 0x23, 0xFA]);
+    let frames: Vec<&[u8]> = written[5..].chunks(19).collect();
+    assert_eq!(frames.len(), 3);
+    for (frame, x) in frames.iter().zip([1100u16, 1200, 1300]) {
+        assert_eq!(frame[3], 0x25);
+        assert_eq!(&frame[4..6], &x.to_be_bytes());
+        assert_eq!(&frame[8..10], &[0x07, 0xD0]);
+        assert_eq!(&frame[14..19], &[0x23, 0x28, 40, 1, 0xFA]);
+    }
+}
+
+#[test]
+fn test_move_linear_rejects_step() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    let target = Coords::from([130.0, 0.0, 200.0, 0.0, 0.0, 90.0]);
+
+    assert!(matches!(robot.move_linear(target, 40, 0.0), Err(Error::InvalidParameter(_))));
+    assert!(matches!(robot.move_linear(target, 40, f32::NAN), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+
+    // 30 mm in steps of 1 um would take 30000 waypoints
+    let mut mock = MockSerial::new();
+    mock.push_read(&[
+        0xFE, 0xFE, 0x0E, 0x23, 0x03, 0xE8, 0x00, 0x00, 0x07, 0xD0, 0x00, 0x00, 0x00, 0x00, 0x23, 0x28, 0xFA,
+    ]);
+    let mut robot = MyCobot::new(mock);
+    assert!(matches!(robot.move_linear(target, 40, 1e-3), Err(Error::InvalidParameter(_))));
+    // Only the coordinates were read
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x02, 0x23, 0xFA]);
+}
+
+#[test]
+fn test_faulty_port_read_errors() {
+    let angles = [0xFE, 0xFE, 0x0E, 0x20, 0x00, 0x64, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0xFA];
+    let mut mock = MockSerial::new();
+    mock.push_read(&angles);
+    mock.push_read(&angles);
+    let mut port = FaultyPort::new(mock);
+    port.fail_read(1, std::io::ErrorKind::BrokenPipe);
+    port.fail_read(2, std::io::ErrorKind::TimedOut);
+    let mut robot = MyCobot::new(port);
+
+    assert!(matches!(robot.get_angles(), Err(Error::Io(e)) if e.kind() == std::io::ErrorKind::BrokenPipe));
+    assert!(matches!(robot.get_angles(), Err(Error::Timeout)));
+    // The responses were never read, so the next requests see them
+    assert_eq!(robot.get_angles_array().unwrap()[0], 1.0);
+    assert_eq!(robot.get_angles_array().unwrap()[0], 1.0);
+    assert_eq!(robot.port.calls().1, 4);
+}
+
+#[test]
+fn test_faulty_port_write_errors() {
+    let mut port = FaultyPort::new(MockSerial::new());
+    port.fail_write(1, std::io::ErrorKind::BrokenPipe);
+    port.truncate_write(2, 3);
+    let mut robot = MyCobot::new(port);
+    robot.set_timeout(Duration::from_millis(10));
+
+    assert!(matches!(robot.power_on(), Err(Error::Io(_))));
+    assert!(robot.port.get_mut().pop_write().is_empty());
+
+    robot.power_on().unwrap();
+    assert_eq!(robot.port.get_mut().pop_write(), vec![0xFE, 0xFE, 0x02]);
+
+    robot.power_on().unwrap();
+    assert_eq!(robot.port.get_mut().pop_write(), vec![0xFE, 0xFE, 0x02, 0x10, 0xFA]);
+}
+
+#[test]
+fn test_clamp_mode() {
+    let mut robot = MyCobot::new(MockSerial::new());
+    assert_eq!(robot.clamp_mode(), ClampMode::Reject);
+    assert!(matches!(robot.write_angles([170.0, 0.0, 0.0, 0.0, 0.0, 0.0], 50), Err(Error::InvalidParameter(_))));
+
+    robot.set_clamp_mode(ClampMode::Clamp);
+    robot.write_angles([170.0, 0.0, -200.0, 0.0, 0.0, 0.0], 50).unwrap();
+    let packet = Packet::from_bytes(&robot.port.pop_write()).unwrap();
+    // 168.0 and -150.0 degrees
+    assert_eq!(&packet.payload[..6], &[0x41, 0xA0, 0x00, 0x00, 0xC5, 0x68]);
+
+    robot.write_angle(1, -200.0, 50).unwrap();
+    let packet = Packet::from_bytes(&robot.port.pop_write()).unwrap();
+    assert_eq!(packet.payload, [1, 0xBE, 0x60, 50]);
+
+    robot.write_coords([300.0, 0.0, 500.0, 0.0, 0.0, 0.0], 50, MoveMode::Linear).unwrap();
+    let packet = Packet::from_bytes(&robot.port.pop_write()).unwrap();
+    // 281.45 and 412.67 mm in tenths
+    assert_eq!(&packet.payload[..6], &[0x0A, 0xFE, 0x00, 0x00, 0x10, 0x1E]);
+
+    // Non-finite values can't be clamped
+    assert!(matches!(robot.write_angles([f32::NAN, 0.0, 0.0, 0.0, 0.0, 0.0], 50), Err(Error::InvalidParameter(_))));
+    assert!(robot.port.pop_write().is_empty());
+}
+
+#[test]
+fn test_queue_commands() {
+    let mut robot = MyCobot::new(FaultyPort::new(MockSerial::new()));
+
+    robot.queue_angles([1.0, 0.0, 0.0, 0.0, 0.0, 0.0], 50).unwrap();
+    robot.queue_command(Command::PowerOn, vec![]).unwrap();
+    robot.queue_coords([100.0, 0.0, 200.0, 0.0, 0.0, 0.0], 50, MoveMode::Linear).unwrap();
+    assert_eq!(robot.port.calls().1, 0);
+
+    robot.flush_commands().unwrap();
+    assert_eq!(robot.port.calls().1, 1);
+    let (packets, consumed) = Packet::parse_all(&robot.port.get_mut().pop_write());
+    assert_eq!(consumed, 18 + 5 + 19);
+    let commands: Vec<Command> = packets.iter().map(|p| p.command).collect();
+    assert_eq!(commands, [Command::WriteAngles, Command::PowerOn, Command::WriteCoords]);
+
+    // Nothing left to write
+    robot.flush_commands().unwrap();
+    assert_eq!(robot.port.calls().1, 1);
+
+    // A direct write sends the queue first
+    robot.queue_command(Command::PowerOff, vec![]).unwrap();
+    robot.stop().unwrap();
+    let (packets, _) = Packet::parse_all(&robot.port.get_mut().pop_write());
+    let commands: Vec<Command> = packets.iter().map(|p| p.command).collect();
+    assert_eq!(commands, [Command::PowerOff, Command::TaskStop]);
+}
+
+#[test]
+fn test_queue_reported_and_kept_until_written() {
+    let frames = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let captured = frames.clone();
+
+    let mut port = FaultyPort::new(MockSerial::new());
+    port.fail_write(1, std::io::ErrorKind::BrokenPipe);
+    let mut robot = MyCobot::new(port);
+    robot.set_frame_observer(Box::new(move |_, frame| captured.lock().unwrap().push(frame.to_vec())));
+
+    robot.queue_command(Command::PowerOn, vec![]).unwrap();
+    robot.queue_command(Command::TaskStop, vec![]).unwrap();
+    assert!(frames.lock().unwrap().is_empty());
+
+    // The failed batch stays queued
+    assert!(matches!(robot.flush_commands(), Err(Error::Io(_))));
+    assert!(robot.port.get_mut().pop_write().is_empty());
+    frames.lock().unwrap().clear();
+
+    robot.flush_commands().unwrap();
+    let expected = vec![vec![0xFE, 0xFE, 0x02, 0x10, 0xFA], vec![0xFE, 0xFE, 0x02, 0x29, 0xFA]];
+    assert_eq!(*frames.lock().unwrap(), expected);
+    assert_eq!(robot.port.get_mut().pop_write(), expected.concat());
+}
+
+#[test]
+fn test_checksum_mode() {
+    let mut mock = MockSerial::new();
+    // GetSpeed answered with 50, checksum 0x40 + 0x32 = 0x72
+    mock.push_read(&[0xFE, 0xFE, 0x04, 0x40, 0x32, 0x72, 0xFA]);
+    mock.push_read(&[0xFE, 0xFE, 0x04, 0x40, 0x32, 0x73, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+    robot.set_checksum(true);
+
+    assert_eq!(robot.get_speed().unwrap(), 50);
+    assert_eq!(robot.port.pop_write(), vec![0xFE, 0xFE, 0x03, 0x40, 0x40, 0xFA]);
+
+    assert!(matches!(robot.get_speed(), Err(Error::ChecksumMismatch)));
 }
@@ -0,0 +1,46 @@
+/*
+ * Copyright (C) 2026 Progress Robotics UG
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+#![cfg(feature = "serde")]
+
+use mycobot_rs::{Coords, JointAngles, MockSerial, MyCobot};
+
+#[test]
+fn test_joint_angles_round_trip() {
+    let angles = JointAngles::from([1.5, -2.0, 30.25, 0.0, 90.0, -180.0]);
+    let json = serde_json::to_string(&angles).unwrap();
+    assert_eq!(json, r#"{"j1":1.5,"j2":-2.0,"j3":30.25,"j4":0.0,"j5":90.0,"j6":-180.0}"#);
+    assert_eq!(serde_json::from_str::<JointAngles>(&json).unwrap(), angles);
+}
+
+#[test]
+fn test_coords_round_trip() {
+    let coords = Coords::from([150.0, -20.5, 300.0, -90.0, 0.0, 45.0]);
+    let json = serde_json::to_string(&coords).unwrap();
+    assert_eq!(serde_json::from_str::<Coords>(&json).unwrap(), coords);
+}
+
+#[test]
+fn test_write_recorded_waypoint() {
+    let waypoint: JointAngles = serde_json::from_str(r#"{"j1":1.0,"j2":0.0,"j3":0.0,"j4":0.0,"j5":0.0,"j6":0.0}"#).unwrap();
+
+    let mut robot = MyCobot::new(MockSerial::new());
+    robot.write_angles(waypoint, 50).unwrap();
+
+    let written = robot.port.pop_write();
+    assert_eq!(&written[3..6], &[0x22, 0x00, 0x64]);
+}
@@ -0,0 +1,71 @@
+/*
+ * Copyright (C) 2026 Progress Robotics UG
+ *
+ * This program is free software: you can redistribute it and/or modify
+ * it under the terms of the GNU General Public License as published by
+ * the Free Software Foundation, either version 3 of the License, or
+ * (at your option) any later version.
+ *
+ * This program is distributed in the hope that it will be useful,
+ * but WITHOUT ANY WARRANTY; without even the implied warranty of
+ * MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+ * GNU General Public License for more details.
+ *
+ * You should have received a copy of the GNU General Public License
+ * along with this program.  If not, see <https://www.gnu.org/licenses/>.
+ */
+
+#![cfg(feature = "tracing")]
+
+use mycobot_rs::{MockSerial, MyCobot};
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Records the names of created and entered spans.
+#[derive(Clone, Default)]
+struct SpanRecorder {
+    created: Arc<Mutex<Vec<&'static str>>>,
+    entered: Arc<Mutex<Vec<&'static str>>>,
+}
+
+impl Subscriber for SpanRecorder {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut created = self.created.lock().unwrap();
+        created.push(span.metadata().name());
+        Id::from_u64(created.len() as u64)
+    }
+
+    fn record(&self, _span: &Id, _values: &Record<'_>) {}
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, span: &Id) {
+        let name = self.created.lock().unwrap()[span.into_u64() as usize - 1];
+        self.entered.lock().unwrap().push(name);
+    }
+
+    fn exit(&self, _span: &Id) {}
+}
+
+#[test]
+fn test_request_span_is_entered() {
+    let mut mock = MockSerial::new();
+    mock.push_read(&[0xFE, 0xFE, 0x03, 0x40, 0x32, 0xFA]);
+    let mut robot = MyCobot::new(mock);
+
+    let recorder = SpanRecorder::default();
+    tracing::subscriber::with_default(recorder.clone(), || {
+        assert_eq!(robot.get_speed().unwrap(), 50);
+        robot.power_on().unwrap();
+    });
+
+    let entered = recorder.entered.lock().unwrap();
+    assert_eq!(*entered, ["mycobot.request", "mycobot.write_command"]);
+}